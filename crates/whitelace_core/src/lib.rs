@@ -13,6 +13,8 @@
 #![deny(clippy::disallowed_types)]
 #![no_std]
 
+extern crate alloc;
+
 pub mod input;
 pub mod main;
 pub mod map;
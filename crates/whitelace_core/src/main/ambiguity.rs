@@ -0,0 +1,74 @@
+use core::fmt;
+
+use bevy::{
+    ecs::schedule::{InternedScheduleLabel, LogLevel},
+    platform::prelude::{String, ToString, Vec},
+};
+
+/// What to do when two systems of a [`Subworld`](super::Subworld) schedule have conflicting
+/// access and no explicit ordering.
+///
+/// The topological order of unordered systems can shift as systems are added, which silently
+/// changes the results of the simulation between builds.
+///
+/// Defaults to [`AmbiguityPolicy::Warn`], so ambiguities show up in the logs without stopping
+/// the app. Opt into [`AmbiguityPolicy::Panic`] to fail on them, e.g. in debug builds or CI.
+/// [`Subworld::check_ambiguities`](super::Subworld::check_ambiguities) lists them regardless of
+/// the policy, e.g. in a test.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AmbiguityPolicy {
+    Ignore,
+    /// Logs the ambiguities when a schedule is built.
+    #[default]
+    Warn,
+    /// Fails the schedule build, and panics in [`Subworld::finish`](super::Subworld::finish).
+    Panic,
+}
+
+impl AmbiguityPolicy {
+    pub(crate) const fn log_level(self) -> LogLevel {
+        match self {
+            Self::Ignore => LogLevel::Ignore,
+            Self::Warn => LogLevel::Warn,
+            Self::Panic => LogLevel::Error,
+        }
+    }
+}
+
+/// A pair of systems with conflicting access and no explicit ordering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemAmbiguity {
+    pub schedule: InternedScheduleLabel,
+    pub first: String,
+    pub second: String,
+    /// Names of the components and resources both systems access. Empty if the systems
+    /// conflict on the whole world, e.g. when one of them is exclusive.
+    pub conflicts: Vec<String>,
+}
+
+impl fmt::Display for SystemAmbiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {} and {}", self.schedule, self.first, self.second)?;
+        if self.conflicts.is_empty() {
+            write!(f, " conflict on the world")
+        } else {
+            write!(f, " conflict on {}", self.conflicts.join(", "))
+        }
+    }
+}
+
+impl SystemAmbiguity {
+    pub(crate) fn new(
+        schedule: InternedScheduleLabel,
+        first: String,
+        second: String,
+        conflicts: impl IntoIterator<Item = impl ToString>,
+    ) -> Self {
+        Self {
+            schedule,
+            first,
+            second,
+            conflicts: conflicts.into_iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use bevy::{
+    ecs::{component::Component, entity::Entity, hierarchy::ChildOf, world::World},
+    platform::prelude::Vec,
+};
+
+use crate::{
+    main::{StableId, StableIds, Subworld, input::UserInput, schedule::SimTick},
+    map::Map,
+    math::{FQuat, FVec3, Fx},
+};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes a component for [`Subworld::checksum`], usually derived with `#[derive(Checksum)]`.
+/// Components are only hashed once registered with [`Subworld::register_checksum`], which the
+/// derive doesn't do.
+pub trait Checksum {
+    fn checksum(&self, hasher: &mut ChecksumHasher);
+}
+
+/// FNV-1a hasher over the little endian bytes of the hashed values, so a checksum is the same
+/// on every platform.
+///
+/// [`Entity`] values are hashed as the [`StableId`] of the entity, since entity indices differ
+/// between peers.
+pub struct ChecksumHasher<'w> {
+    hash: u64,
+    world: &'w World,
+}
+
+impl<'w> ChecksumHasher<'w> {
+    #[must_use]
+    pub const fn new(world: &'w World) -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+            world,
+        }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= u64::from(*byte);
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Hashes the [`StableId`] of `entity`, or [`StableId::PENDING`] if it has none.
+    pub fn write_entity(&mut self, entity: Entity) {
+        let id = self
+            .world
+            .get::<StableId>(entity)
+            .copied()
+            .unwrap_or(StableId::PENDING);
+        id.checksum(self);
+    }
+
+    #[must_use]
+    pub const fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl<I: UserInput> Subworld<I> {
+    /// Hashes the current tick and the components registered with
+    /// [`Subworld::register_checksum`] of every entity with a [`StableId`], in id order.
+    ///
+    /// Peers running the same simulation get the same checksum, so comparing them detects
+    /// desyncs.
+    #[must_use]
+    pub fn checksum(&self) -> u64 {
+        let entities: Vec<_> = self.world.resource::<StableIds>().iter().collect();
+
+        let mut hasher = ChecksumHasher::new(&self.world);
+        self.world.resource::<SimTick>().get().checksum(&mut hasher);
+        for (id, entity) in entities {
+            id.checksum(&mut hasher);
+            for checksum in self.checksums.values() {
+                checksum(&self.world, entity, &mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+/// Hashes `C` of `entity`, or only its absence.
+pub(crate) fn checksum_component<C: Component + Checksum>(
+    world: &World,
+    entity: Entity,
+    hasher: &mut ChecksumHasher,
+) {
+    let component = world.get::<C>(entity);
+    component.checksum(hasher);
+}
+
+macro_rules! impl_checksum_for_int {
+    ($($ty:ty),*) => {$(
+        impl Checksum for $ty {
+            fn checksum(&self, hasher: &mut ChecksumHasher) {
+                hasher.write_bytes(&self.to_le_bytes());
+            }
+        }
+    )*};
+}
+
+impl_checksum_for_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+// Hashed as 64 bits, so checksums don't depend on the pointer width.
+impl Checksum for usize {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        u64::try_from(*self).unwrap().checksum(hasher);
+    }
+}
+
+impl Checksum for isize {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        i64::try_from(*self).unwrap().checksum(hasher);
+    }
+}
+
+impl Checksum for bool {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        u8::from(*self).checksum(hasher);
+    }
+}
+
+impl Checksum for () {
+    fn checksum(&self, _: &mut ChecksumHasher) {}
+}
+
+impl Checksum for Fx {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        self.to_bits().checksum(hasher);
+    }
+}
+
+impl Checksum for FVec3 {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        self.x.checksum(hasher);
+        self.y.checksum(hasher);
+        self.z.checksum(hasher);
+    }
+}
+
+impl Checksum for FQuat {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        self.x.checksum(hasher);
+        self.y.checksum(hasher);
+        self.z.checksum(hasher);
+        self.w.checksum(hasher);
+    }
+}
+
+impl Checksum for Entity {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        hasher.write_entity(*self);
+    }
+}
+
+impl Checksum for StableId {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        self.to_raw().checksum(hasher);
+    }
+}
+
+impl Checksum for ChildOf {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        self.parent().checksum(hasher);
+    }
+}
+
+impl<T: Checksum> Checksum for &T {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        (**self).checksum(hasher);
+    }
+}
+
+impl<T: Checksum> Checksum for Option<T> {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        match self {
+            Some(value) => {
+                true.checksum(hasher);
+                value.checksum(hasher);
+            }
+            None => false.checksum(hasher),
+        }
+    }
+}
+
+impl<T: Checksum, const N: usize> Checksum for [T; N] {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        for value in self {
+            value.checksum(hasher);
+        }
+    }
+}
+
+impl<T: Checksum> Checksum for Vec<T> {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        self.len().checksum(hasher);
+        for value in self {
+            value.checksum(hasher);
+        }
+    }
+}
+
+impl<K: Checksum, V: Checksum> Checksum for Map<K, V> {
+    fn checksum(&self, hasher: &mut ChecksumHasher) {
+        self.len().checksum(hasher);
+        for (key, value) in self {
+            key.checksum(hasher);
+            value.checksum(hasher);
+        }
+    }
+}
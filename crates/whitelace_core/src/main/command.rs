@@ -0,0 +1,130 @@
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use bevy::{
+    ecs::{resource::Resource, system::Command, world::World},
+    platform::sync::Arc,
+};
+
+use crate::main::schedule::SimTick;
+
+/// Identifies a command added to [`ScheduledCommands`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScheduledCommandId(u64);
+
+type ScheduledCommand = Arc<dyn Fn(&mut World) + Send + Sync>;
+
+/// Commands to run on a future tick of a [`Subworld`](super::Subworld).
+///
+/// Due commands run at the start of the tick, before the first schedule of the
+/// [`ScheduleOrder`](super::schedule::ScheduleOrder), ordered by tick and then by the order they
+/// were scheduled in. A command scheduled for a tick that already started runs at the start of
+/// the next one.
+///
+/// The resource is part of [`WorldSnapshot`](super::WorldSnapshot)s. Commands are shared, not
+/// consumed, when it is cloned, so the commands of a restored snapshot run again when its ticks
+/// are re-simulated. They shouldn't keep any state of their own.
+///
+/// Commands are closures, so they can't be written to a save: serializing the world fails while
+/// any of them is pending.
+#[derive(Resource, Default, Clone)]
+pub struct ScheduledCommands {
+    tick: SimTick,
+    next_id: u64,
+    commands: BTreeMap<(SimTick, ScheduledCommandId), ScheduledCommand>,
+}
+
+impl ScheduledCommands {
+    /// Schedules `command` to run at the start of `tick`.
+    pub fn schedule<C: Command + Clone + Sync>(
+        &mut self,
+        tick: SimTick,
+        command: C,
+    ) -> ScheduledCommandId {
+        let id = ScheduledCommandId(self.next_id);
+        self.next_id += 1;
+        let command: ScheduledCommand = Arc::new(move |world: &mut World| {
+            command.clone().apply(world);
+        });
+        self.commands.insert((tick, id), command);
+        id
+    }
+
+    /// Schedules `command` to run `ticks` ticks after the current [`SimTick`].
+    ///
+    /// During a tick that is the tick being run, so `1` runs `command` at the start of the next
+    /// tick. Between ticks [`SimTick`] is already the next tick, so `0` runs it at its start.
+    pub fn schedule_in<C: Command + Clone + Sync>(
+        &mut self,
+        ticks: u64,
+        command: C,
+    ) -> ScheduledCommandId {
+        self.schedule(SimTick::new(self.tick.get() + ticks), command)
+    }
+
+    /// Returns `true` if the command was still pending.
+    pub fn cancel(&mut self, id: ScheduledCommandId) -> bool {
+        let key = self
+            .commands
+            .keys()
+            .find(|(_, other)| *other == id)
+            .copied();
+        key.is_some_and(|key| self.commands.remove(&key).is_some())
+    }
+
+    /// The current [`SimTick`], that [`ScheduledCommands::schedule_in`] is relative to.
+    #[must_use]
+    pub const fn tick(&self) -> SimTick {
+        self.tick
+    }
+
+    #[must_use]
+    pub fn next_tick(&self) -> Option<SimTick> {
+        self.commands.keys().next().map(|(tick, _)| *tick)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Keeps [`ScheduledCommands::tick`] in sync with the [`SimTick`].
+    pub(crate) fn set_tick(world: &mut World) {
+        let tick = *world.resource::<SimTick>();
+        if let Some(mut commands) = world.get_resource_mut::<Self>() {
+            commands.tick = tick;
+        }
+    }
+
+    /// Runs every command due at the current [`SimTick`], including the ones scheduled for
+    /// it by those commands.
+    pub(crate) fn run_due(world: &mut World) {
+        Self::set_tick(world);
+        let tick = *world.resource::<SimTick>();
+
+        loop {
+            let mut commands = world.resource_mut::<Self>();
+            if commands.next_tick().is_none_or(|next| next > tick) {
+                break;
+            }
+            let (_, command) = commands.commands.pop_first().unwrap();
+            command(world);
+            world.flush();
+        }
+    }
+}
+
+impl fmt::Debug for ScheduledCommands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScheduledCommands")
+            .field("tick", &self.tick)
+            .field("next_id", &self.next_id)
+            .field("commands", &self.commands.keys())
+            .finish()
+    }
+}
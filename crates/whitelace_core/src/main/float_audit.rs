@@ -0,0 +1,185 @@
+use core::{any::TypeId, fmt};
+
+use bevy::{
+    ecs::component::ComponentInfo,
+    math::{DQuat, DVec2, DVec3, DVec4, Mat2, Mat3, Mat3A, Mat4, Quat, Vec2, Vec3, Vec3A, Vec4},
+    platform::prelude::{String, ToString, Vec},
+    reflect::{Type, TypeInfo, TypeRegistry, VariantInfo},
+};
+
+/// What a [`Subworld`](super::Subworld) does when the float audit finds a floating point field
+/// in one of its components or resources, or a type it can't inspect. The audit only runs with
+/// debug assertions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatPolicy {
+    Ignore,
+    #[default]
+    Warn,
+    Panic,
+}
+
+/// A floating point field of a component or resource, found by
+/// [`Subworld::audit_floats`](super::Subworld::audit_floats).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FloatField {
+    /// Type path of the component or resource.
+    pub owner: String,
+    /// Path of the field inside `owner`, e.g. `.speed` or `::Moving.0`. Empty for `owner`
+    /// itself.
+    pub field: String,
+    /// Type path of the field, or `None` if the field is missing from the type registry and
+    /// might hide floats.
+    pub ty: Option<&'static str>,
+}
+
+impl fmt::Display for FloatField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ty {
+            Some(ty) => write!(f, "{}{}: {ty}", self.owner, self.field),
+            None => write!(
+                f,
+                "{}{}: not registered for reflection, can't be audited",
+                self.owner, self.field
+            ),
+        }
+    }
+}
+
+fn is_float(id: TypeId) -> bool {
+    [
+        TypeId::of::<f32>(),
+        TypeId::of::<f64>(),
+        TypeId::of::<Vec2>(),
+        TypeId::of::<Vec3>(),
+        TypeId::of::<Vec3A>(),
+        TypeId::of::<Vec4>(),
+        TypeId::of::<Quat>(),
+        TypeId::of::<Mat2>(),
+        TypeId::of::<Mat3>(),
+        TypeId::of::<Mat3A>(),
+        TypeId::of::<Mat4>(),
+        TypeId::of::<DVec2>(),
+        TypeId::of::<DVec3>(),
+        TypeId::of::<DVec4>(),
+        TypeId::of::<DQuat>(),
+    ]
+    .contains(&id)
+}
+
+/// Walks the reflected fields of the component or resource `owner`. Types missing from the
+/// registry can't be inspected and are reported as well, unless they are zero-sized.
+pub(crate) fn find_float_fields(registry: &TypeRegistry, owner: &ComponentInfo) -> Vec<FloatField> {
+    let mut audit = Audit {
+        registry,
+        owner: owner.name().to_string(),
+        path: String::new(),
+        stack: Vec::new(),
+        found: Vec::new(),
+    };
+    match owner.type_id().and_then(|id| registry.get_type_info(id)) {
+        Some(info) => {
+            audit.owner = info.type_path().to_string();
+            audit.visit(info.ty());
+        }
+        None if owner.layout().size() > 0 => audit.found.push(FloatField {
+            owner: audit.owner.clone(),
+            field: String::new(),
+            ty: None,
+        }),
+        None => {}
+    }
+    audit.found
+}
+
+struct Audit<'a> {
+    registry: &'a TypeRegistry,
+    owner: String,
+    path: String,
+    stack: Vec<TypeId>,
+    found: Vec<FloatField>,
+}
+
+impl Audit<'_> {
+    fn visit(&mut self, ty: &Type) {
+        if is_float(ty.id()) {
+            self.found.push(FloatField {
+                owner: self.owner.clone(),
+                field: self.path.clone(),
+                ty: Some(ty.path()),
+            });
+            return;
+        }
+        // Recursive types (e.g. trees) would otherwise never finish.
+        if self.stack.contains(&ty.id()) {
+            return;
+        }
+        let Some(info) = self.registry.get_type_info(ty.id()) else {
+            self.found.push(FloatField {
+                owner: self.owner.clone(),
+                field: self.path.clone(),
+                ty: None,
+            });
+            return;
+        };
+
+        self.stack.push(ty.id());
+        match info {
+            TypeInfo::Struct(info) => {
+                for field in info.iter() {
+                    self.visit_field(&[".", field.name()], field.ty());
+                }
+            }
+            TypeInfo::TupleStruct(info) => {
+                for field in info.iter() {
+                    self.visit_field(&[".", &field.index().to_string()], field.ty());
+                }
+            }
+            TypeInfo::Tuple(info) => {
+                for field in info.iter() {
+                    self.visit_field(&[".", &field.index().to_string()], field.ty());
+                }
+            }
+            TypeInfo::List(info) => self.visit_field(&["[]"], &info.item_ty()),
+            TypeInfo::Array(info) => self.visit_field(&["[]"], &info.item_ty()),
+            TypeInfo::Set(info) => self.visit_field(&["[]"], &info.value_ty()),
+            TypeInfo::Map(info) => {
+                self.visit_field(&["[key]"], &info.key_ty());
+                self.visit_field(&["[]"], &info.value_ty());
+            }
+            TypeInfo::Enum(info) => {
+                for variant in info.iter() {
+                    match variant {
+                        VariantInfo::Struct(variant) => {
+                            for field in variant.iter() {
+                                self.visit_field(
+                                    &["::", variant.name(), ".", field.name()],
+                                    field.ty(),
+                                );
+                            }
+                        }
+                        VariantInfo::Tuple(variant) => {
+                            for field in variant.iter() {
+                                self.visit_field(
+                                    &["::", variant.name(), ".", &field.index().to_string()],
+                                    field.ty(),
+                                );
+                            }
+                        }
+                        VariantInfo::Unit(_) => {}
+                    }
+                }
+            }
+            TypeInfo::Opaque(_) => {}
+        }
+        self.stack.pop();
+    }
+
+    fn visit_field(&mut self, segments: &[&str], ty: &Type) {
+        let len = self.path.len();
+        for segment in segments {
+            self.path.push_str(segment);
+        }
+        self.visit(ty);
+        self.path.truncate(len);
+    }
+}
@@ -0,0 +1,89 @@
+use core::any::TypeId;
+
+use bevy::{
+    ecs::{reflect::AppTypeRegistry, resource::Resource, world::World},
+    log::warn,
+};
+
+use crate::main::{Subworld, input::UserInput};
+
+impl<I: UserInput> Subworld<I> {
+    /// Creates an independent copy of this world, e.g. to simulate hypothetical inputs ahead
+    /// for AI lookahead or trajectory previews, without affecting this world.
+    ///
+    /// The schedules are built by [`Subworld::rebuild`], so systems and observers added outside
+    /// of a [`DPlugin`](super::DPlugin) are not part of the fork, and a warning is logged if this
+    /// world has such systems. Registrations like [`Subworld::register_snapshot`] or
+    /// [`Subworld::add_message`] are copied, wherever they were made. The state is copied as
+    /// with [`Subworld::reset_fork`].
+    ///
+    /// Building the schedules is the expensive part, so keep a fork around and bring it back
+    /// to the current state with [`Subworld::reset_fork`] instead of forking every tick.
+    #[must_use]
+    pub fn fork(&self) -> Self {
+        if self.loose_systems {
+            warn!(
+                "Forking a Subworld with systems added outside of a DPlugin, the fork won't run them"
+            );
+        }
+        let mut fork = self.rebuild();
+        // The plugins made the same registrations in the same order, so this only adds the ones
+        // made outside of them.
+        fork.entity_mappings.clone_from(&self.entity_mappings);
+        fork.message_queues.clone_from(&self.message_queues);
+        fork.snapshots.clone_from(&self.snapshots);
+        fork.snapshot_resources.clone_from(&self.snapshot_resources);
+        fork.fork_resources.clone_from(&self.fork_resources);
+        fork.checksums.clone_from(&self.checksums);
+        fork.float_audit_skipped
+            .clone_from(&self.float_audit_skipped);
+        self.reset_fork(&mut fork);
+        fork
+    }
+
+    /// Creates a new world with the plugins and settings of this world but none of its state,
+    /// so its startup systems run again on the first tick.
+    ///
+    /// Like [`Subworld::fork`], systems and observers added outside of a
+    /// [`DPlugin`](super::DPlugin) are not part of the new world.
+    #[must_use]
+    pub fn rebuild(&self) -> Self {
+        let mut world = Self::empty(self.world.resource::<AppTypeRegistry>().clone());
+        world.executor = self.executor;
+        world.ambiguity_policy = self.ambiguity_policy;
+        world.float_policy = self.float_policy;
+        for plugin in &self.root_plugins {
+            world.add_shared_plugin(plugin.clone());
+        }
+        world
+    }
+
+    /// Resets `fork` to the current state of this world, keeping its schedules.
+    ///
+    /// The components and resources registered with [`Subworld::register_snapshot`] and
+    /// [`Subworld::register_snapshot_resource`] are copied as with [`Subworld::restore`], along
+    /// with the resources registered with [`Subworld::register_fork_resource`]. Anything else
+    /// keeps the state of `fork`.
+    pub fn reset_fork(&self, fork: &mut Self) {
+        fork.restore(&self.snapshot());
+        for copy in self.fork_resources.values() {
+            copy(&self.world, &mut fork.world);
+        }
+    }
+
+    /// Registers `R` to be cloned into forks, see [`Subworld::fork`].
+    pub fn register_fork_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.fork_resources
+            .insert(TypeId::of::<R>(), copy_resource::<R>);
+        self
+    }
+}
+
+fn copy_resource<R: Resource + Clone>(from: &World, to: &mut World) {
+    match from.get_resource::<R>() {
+        Some(resource) => to.insert_resource(resource.clone()),
+        None => {
+            to.remove_resource::<R>();
+        }
+    }
+}
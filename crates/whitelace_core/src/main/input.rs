@@ -33,6 +33,12 @@ impl<I: UserInput> Default for FrameInput<I> {
 }
 
 impl<I: UserInput> FrameInput<I> {
+    /// The input of the tick being run.
+    #[must_use]
+    pub fn get(&self) -> &[I] {
+        &self.frame
+    }
+
     pub(crate) fn set(&mut self, input: Vec<I>) {
         let temp = core::mem::take(&mut self.frame);
         self.history.extend(temp);
@@ -0,0 +1,136 @@
+use core::slice;
+
+use bevy::{
+    ecs::system::SystemParam,
+    platform::prelude::Vec,
+    prelude::{Res, ResMut, Resource, World},
+};
+
+use crate::main::schedule::SimTick;
+
+/// Identifies a message written to [`DMessages`]: the tick it was written in and its position
+/// among the messages of that tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DMessageId {
+    pub tick: SimTick,
+    pub sequence: u32,
+}
+
+/// A deterministic message queue of a [`Subworld`](super::Subworld), added with
+/// [`Subworld::add_message`](super::Subworld::add_message).
+///
+/// Holds the messages written during the current tick, in the order they were written. Writers
+/// need mutable access, so unordered writers are reported as ambiguous under the
+/// [`AmbiguityPolicy`](super::AmbiguityPolicy), and once they are ordered the order of messages
+/// follows the system order. Readers see the messages written so far in the tick, so they have
+/// to run after the writers.
+///
+/// Unlike bevy's `Messages`, the queue is not double-buffered: it is cleared before each tick,
+/// so a message only lives for the tick it was written in. A message written after its last
+/// reader, e.g. in [`PostFixedUpdate`](super::schedule::PostFixedUpdate), is never seen by
+/// the next tick. Between ticks the queue still holds the messages of the last one, so sync
+/// systems can read them. The queue is part of [`WorldSnapshot`](super::WorldSnapshot)s, so
+/// it is restored with them and copied into forks.
+#[derive(Resource, Debug, Clone)]
+pub struct DMessages<M: Send + Sync + 'static> {
+    tick: SimTick,
+    messages: Vec<(DMessageId, M)>,
+}
+
+impl<M: Send + Sync + 'static> Default for DMessages<M> {
+    fn default() -> Self {
+        Self {
+            tick: SimTick::default(),
+            messages: Vec::new(),
+        }
+    }
+}
+
+impl<M: Send + Sync + 'static> DMessages<M> {
+    pub fn write(&mut self, message: M) -> DMessageId {
+        let id = DMessageId {
+            tick: self.tick,
+            sequence: u32::try_from(self.messages.len()).unwrap(),
+        };
+        self.messages.push((id, message));
+        id
+    }
+
+    pub fn write_batch(&mut self, messages: impl IntoIterator<Item = M>) {
+        for message in messages {
+            self.write(message);
+        }
+    }
+
+    /// The tick the messages were written in.
+    #[must_use]
+    pub const fn tick(&self) -> SimTick {
+        self.tick
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &M> + '_ {
+        self.messages.iter().map(|(_, message)| message)
+    }
+
+    pub fn iter_with_id(&self) -> slice::Iter<'_, (DMessageId, M)> {
+        self.messages.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub(crate) fn start_tick(world: &mut World, tick: SimTick) {
+        let mut queue = world.resource_mut::<Self>();
+        queue.tick = tick;
+        queue.messages.clear();
+    }
+}
+
+/// Writes messages to [`DMessages`].
+#[derive(SystemParam)]
+pub struct DMessageWriter<'w, M: Send + Sync + 'static> {
+    messages: ResMut<'w, DMessages<M>>,
+}
+
+impl<M: Send + Sync + 'static> DMessageWriter<'_, M> {
+    pub fn write(&mut self, message: M) -> DMessageId {
+        self.messages.write(message)
+    }
+
+    pub fn write_batch(&mut self, messages: impl IntoIterator<Item = M>) {
+        self.messages.write_batch(messages);
+    }
+}
+
+/// Reads the messages written to [`DMessages`] during the current tick.
+#[derive(SystemParam)]
+pub struct DMessageReader<'w, M: Send + Sync + 'static> {
+    messages: Res<'w, DMessages<M>>,
+}
+
+impl<M: Send + Sync + 'static> DMessageReader<'_, M> {
+    pub fn read(&self) -> impl ExactSizeIterator<Item = &M> + '_ {
+        self.messages.iter()
+    }
+
+    pub fn read_with_id(&self) -> slice::Iter<'_, (DMessageId, M)> {
+        self.messages.iter_with_id()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
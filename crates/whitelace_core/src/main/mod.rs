@@ -1,77 +1,574 @@
+mod ambiguity;
+mod checksum;
+mod command;
+mod float_audit;
+mod fork;
 pub mod input;
+mod message;
+mod plugin;
+#[cfg(feature = "serialize")]
+mod save;
 pub mod schedule;
+mod snapshot;
+mod sorted_query;
+mod stable_id;
+mod state;
 
-use core::ops::{Deref, DerefMut};
+use core::{
+    any::{Any, TypeId},
+    fmt::Write,
+    ops::{Deref, DerefMut},
+};
 
 use bevy::{
     ecs::{
-        schedule::{IntoScheduleConfigs, ScheduleLabel, Schedules},
-        system::ScheduleSystem,
+        change_detection::Tick,
+        component::{Component, ComponentId, ComponentInfo, Mutable},
+        entity::{Entity, EntityMapper},
+        entity_disabling::DefaultQueryFilters,
+        hierarchy::{ChildOf, Children},
+        observer::Observer,
+        reflect::AppTypeRegistry,
+        resource::Resource,
+        schedule::{
+            InternedSystemSet, IntoScheduleConfigs, NodeId, Schedule, ScheduleLabel, Schedules,
+            SystemKey,
+        },
+        system::{IntoSystem, RunSystemError, RunSystemOnce, ScheduleSystem},
         world::World,
     },
-    platform::prelude::vec::Vec,
+    log::warn,
+    platform::{
+        prelude::{Box, String, ToString, Vec},
+        sync::Arc,
+    },
+    reflect::GetTypeRegistration,
 };
+pub use whitelace_macros::{Checksum, Snapshot};
 
-use crate::main::{
-    input::{FrameInput, UserInput},
-    schedule::{FixedSchedule, SchedulePlugin},
+#[cfg(feature = "serialize")]
+pub use crate::main::save::{SavedSubworld, SubworldDeserializer, SubworldSerializer};
+pub use crate::main::{
+    ambiguity::{AmbiguityPolicy, SystemAmbiguity},
+    checksum::{Checksum, ChecksumHasher},
+    command::{ScheduledCommandId, ScheduledCommands},
+    float_audit::{FloatField, FloatPolicy},
+    message::{DMessageId, DMessageReader, DMessageWriter, DMessages},
+    plugin::{DPlugin, DPluginGroup, DPluginGroupBuilder, DPluginsState},
+    snapshot::{Snapshot, SnapshotReader, SnapshotWriter, WorldSnapshot},
+    sorted_query::{SortedCombinations, SortedQuery},
+    stable_id::{StableId, StableIds},
+};
+use crate::{
+    main::{
+        checksum::checksum_component,
+        float_audit::find_float_fields,
+        input::{FrameInput, UserInput},
+        schedule::{FixedSchedule, SchedulePlugin, SimTick, SubworldExecutor},
+        snapshot::{SnapshotFns, SnapshotResourceFns},
+        stable_id::map_component_entities,
+    },
+    map::{Map, Set},
 };
-
-pub trait DPlugin<I: UserInput> {
-    fn build(&self, world: &mut Subworld<I>);
-}
 
 pub struct Subworld<I: UserInput = ()> {
     world: World,
+    plugins: Vec<Arc<dyn DPlugin<I>>>,
+    /// Plugins added directly rather than by another plugin, rebuilt by [`Subworld::fork`].
+    root_plugins: Vec<Arc<dyn DPlugin<I>>>,
+    building_plugins: usize,
+    /// Whether systems were added outside of a [`DPlugin`], which forks don't have.
+    loose_systems: bool,
+    plugin_names: Set<String>,
+    plugins_state: DPluginsState,
+    executor: SubworldExecutor,
+    ambiguity_policy: AmbiguityPolicy,
+    entity_mappings: Map<TypeId, fn(&mut World, &mut dyn EntityMapper)>,
+    message_queues: Map<TypeId, fn(&mut World, SimTick)>,
+    snapshots: Map<TypeId, SnapshotFns>,
+    snapshot_resources: Map<TypeId, SnapshotResourceFns>,
+    fork_resources: Map<TypeId, fn(&World, &mut World)>,
+    /// The change tick the last [`Subworld::tick`] started at, see [`Subworld::snapshot`].
+    tick_start: Tick,
+    /// The transition sets of the last state added, see [`Subworld::init_state`].
+    state_sets: Option<[InternedSystemSet; 3]>,
+    checksums: Map<TypeId, for<'w> fn(&'w World, Entity, &mut ChecksumHasher<'w>)>,
+    float_policy: FloatPolicy,
+    float_audit_skipped: Set<TypeId>,
+    #[cfg(debug_assertions)]
+    audited_components: Set<ComponentId>,
     _phantom: core::marker::PhantomData<I>,
 }
 
 impl<I: UserInput> Default for Subworld<I> {
     fn default() -> Self {
-        let world = World::new();
+        let mut instance = Self::empty(AppTypeRegistry::default());
+        instance.add_plugin(SchedulePlugin);
+        instance
+    }
+}
+
+impl<I: UserInput> Subworld<I> {
+    /// A world without any plugin, not even the [`SchedulePlugin`].
+    fn empty(registry: AppTypeRegistry) -> Self {
         let mut instance = Self {
-            world,
+            world: World::new(),
+            plugins: Vec::new(),
+            root_plugins: Vec::new(),
+            building_plugins: 0,
+            loose_systems: false,
+            plugin_names: Set::default(),
+            plugins_state: DPluginsState::Adding,
+            executor: SubworldExecutor::default(),
+            ambiguity_policy: AmbiguityPolicy::default(),
+            entity_mappings: Map::default(),
+            message_queues: Map::default(),
+            snapshots: Map::default(),
+            snapshot_resources: Map::default(),
+            fork_resources: Map::default(),
+            tick_start: Tick::new(0),
+            state_sets: None,
+            checksums: Map::default(),
+            float_policy: FloatPolicy::default(),
+            float_audit_skipped: Set::default(),
+            #[cfg(debug_assertions)]
+            audited_components: Set::default(),
             _phantom: core::marker::PhantomData,
         };
 
         instance.init_resource::<FrameInput<I>>();
-        instance.add_plugin(SchedulePlugin);
+        instance.init_resource::<StableIds>();
+        instance.set_type_registry(registry);
+        // Bookkeeping of bevy and of this crate, none of it holds floats.
+        instance
+            .skip_float_audit::<Observer>()
+            .skip_float_audit::<DefaultQueryFilters>()
+            .skip_float_audit::<Schedules>()
+            .skip_float_audit::<AppTypeRegistry>()
+            .skip_float_audit::<FrameInput<I>>()
+            .skip_float_audit::<StableIds>();
+        instance
+            .register_snapshot::<ChildOf>()
+            .register_checksum::<ChildOf>();
 
         instance
     }
-}
 
-impl<I: UserInput> Subworld<I> {
+    /// # Panics
+    ///
+    /// Panics if a unique plugin with the same name has already been added.
     pub fn add_plugin(&mut self, plugin: impl DPlugin<I>) -> &mut Self {
-        plugin.build(self);
+        self.add_boxed_plugin(Box::new(plugin));
+        self
+    }
+
+    /// Adds every plugin of the `group`, skipping the ones that are already added.
+    pub fn add_plugins(&mut self, group: impl DPluginGroup<I>) -> &mut Self {
+        group.build().finish(self);
         self
     }
 
+    /// Adds `P` if it hasn't been added yet. Used by plugins to declare their dependencies.
+    pub fn require_plugin<P: DPlugin<I> + Default>(&mut self) -> &mut Self {
+        if !self.is_plugin_added::<P>() {
+            self.add_plugin(P::default());
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn is_plugin_added<P: DPlugin<I>>(&self) -> bool {
+        self.plugins
+            .iter()
+            .any(|plugin| (&**plugin as &dyn Any).is::<P>())
+    }
+
+    #[must_use]
+    pub fn get_added_plugins<P: DPlugin<I>>(&self) -> Vec<&P> {
+        self.plugins
+            .iter()
+            .filter_map(|plugin| (&**plugin as &dyn Any).downcast_ref::<P>())
+            .collect()
+    }
+
+    #[must_use]
+    pub const fn plugins_state(&self) -> DPluginsState {
+        self.plugins_state
+    }
+
+    /// Runs [`DPlugin::finish`] on every plugin. Called automatically before the first tick.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`AmbiguityPolicy`] is [`AmbiguityPolicy::Panic`] and any schedule has
+    /// ambiguous system orderings, see [`Subworld::check_ambiguities`].
+    pub fn finish(&mut self) {
+        if self.plugins_state != DPluginsState::Adding {
+            return;
+        }
+
+        let plugins = core::mem::take(&mut self.plugins);
+        for plugin in &plugins {
+            plugin.finish(self);
+        }
+        self.plugins.splice(0..0, plugins);
+        self.plugins_state = DPluginsState::Finished;
+
+        if self.executor.effective_policy(self.ambiguity_policy) == AmbiguityPolicy::Panic {
+            let ambiguities = self.check_ambiguities();
+            if !ambiguities.is_empty() {
+                let mut report = String::new();
+                for ambiguity in &ambiguities {
+                    let _ = writeln!(report, "  {ambiguity}");
+                }
+                panic!(
+                    "{} pairs of systems have conflicting access and no explicit ordering:\n{report}",
+                    ambiguities.len()
+                );
+            }
+        }
+    }
+
+    /// Runs [`DPlugin::cleanup`] on every plugin. Called automatically before the first tick.
+    pub fn cleanup(&mut self) {
+        self.finish();
+        if self.plugins_state != DPluginsState::Finished {
+            return;
+        }
+
+        let plugins = core::mem::take(&mut self.plugins);
+        for plugin in &plugins {
+            plugin.cleanup(self);
+        }
+        self.plugins.splice(0..0, plugins);
+        self.plugins_state = DPluginsState::Cleaned;
+    }
+
+    pub(crate) fn is_plugin_name_added(&self, name: &str) -> bool {
+        self.plugin_names.contains(name)
+    }
+
+    pub(crate) fn add_boxed_plugin(&mut self, plugin: Box<dyn DPlugin<I>>) {
+        self.add_shared_plugin(Arc::from(plugin));
+    }
+
+    fn add_shared_plugin(&mut self, plugin: Arc<dyn DPlugin<I>>) {
+        if plugin.is_unique() && !self.plugin_names.insert(plugin.name().to_string()) {
+            panic!(
+                "Error adding plugin {}: plugin was already added to the world",
+                plugin.name()
+            );
+        }
+
+        if self.building_plugins == 0 {
+            self.root_plugins.push(plugin.clone());
+        }
+
+        // Keep the registration order of nested plugins added during `build`.
+        let index = self.plugins.len();
+        self.building_plugins += 1;
+        plugin.build(self);
+        self.building_plugins -= 1;
+        self.plugins.insert(index, plugin);
+    }
+
+    /// Adds systems to a schedule of this world, creating it if needed.
+    ///
+    /// Systems can't be copied, so [`Subworld::fork`] and [`Subworld::rebuild`] only have the
+    /// systems added by [`DPlugin`]s.
     pub fn add_systems<M>(
         &mut self,
         schedule: impl ScheduleLabel,
         systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
     ) -> &mut Self {
+        if self.building_plugins == 0 {
+            self.loose_systems = true;
+        }
+        let (executor, policy) = (self.executor, self.ambiguity_policy);
         let mut schedules = self.world.resource_mut::<Schedules>();
-        schedules.add_systems(schedule, systems);
+        let label = schedule.intern();
+        if !schedules.contains(label) {
+            let mut schedule = Schedule::new(label);
+            executor.configure(&mut schedule, policy);
+            schedules.insert(schedule);
+        }
+        schedules.add_systems(label, systems);
 
         self
     }
 
+    #[must_use]
+    pub const fn executor(&self) -> SubworldExecutor {
+        self.executor
+    }
+
+    /// Sets the executor used by every schedule of this world, including ones added later.
+    pub fn set_executor(&mut self, executor: SubworldExecutor) -> &mut Self {
+        self.executor = executor;
+        self.configure_schedules();
+        self
+    }
+
+    #[must_use]
+    pub const fn ambiguity_policy(&self) -> AmbiguityPolicy {
+        self.ambiguity_policy
+    }
+
+    /// Sets how ambiguous system orderings are reported by every schedule of this world.
+    pub fn set_ambiguity_policy(&mut self, policy: AmbiguityPolicy) -> &mut Self {
+        self.ambiguity_policy = policy;
+        self.configure_schedules();
+        self
+    }
+
+    /// Builds every schedule of this world and returns all pairs of systems with conflicting
+    /// access and no explicit ordering, regardless of the [`AmbiguityPolicy`].
+    pub fn check_ambiguities(&mut self) -> Vec<SystemAmbiguity> {
+        let fixed_schedule = FixedSchedule.intern();
+        let labels: Vec<_> = self
+            .world
+            .resource::<Schedules>()
+            .iter()
+            .map(|(_, schedule)| schedule.label())
+            .filter(|label| *label != fixed_schedule)
+            .collect();
+
+        let mut ambiguities = Vec::new();
+        for label in labels {
+            self.world.schedule_scope(label, |world, schedule| {
+                // Ambiguity errors are collected below, any other build error
+                // is reported when the schedule runs.
+                let _ = schedule.initialize(world);
+                for (first, second, conflicts) in schedule.graph().conflicting_systems().iter() {
+                    let conflicts = conflicts
+                        .iter()
+                        .filter_map(|&id| world.components().get_name(id));
+                    ambiguities.push(SystemAmbiguity::new(
+                        label,
+                        system_name(schedule, *first),
+                        system_name(schedule, *second),
+                        conflicts,
+                    ));
+                }
+            });
+        }
+        ambiguities
+    }
+
+    fn configure_schedules(&mut self) {
+        let (executor, policy) = (self.executor, self.ambiguity_policy);
+        let fixed_schedule = FixedSchedule.intern();
+        let mut schedules = self.world.resource_mut::<Schedules>();
+        for (_, schedule) in schedules.iter_mut() {
+            // The runner only drives the other schedules, it has nothing to parallelize.
+            if schedule.label() != fixed_schedule {
+                executor.configure(schedule, policy);
+            }
+        }
+    }
+
+    /// Replaces the type registry of this world, e.g. to share the one of the main app, and
+    /// registers the types of this crate in it.
+    pub fn set_type_registry(&mut self, registry: AppTypeRegistry) -> &mut Self {
+        self.insert_resource(registry);
+        self.register_type::<ChildOf>()
+            .register_type::<Children>()
+            .register_type::<StableId>()
+            .register_type::<SimTick>()
+    }
+
+    /// Registers `T` for reflection, which lets the float audit inspect its fields and
+    /// includes it in saves, see [`Subworld::save_ron`].
+    pub fn register_type<T: GetTypeRegistration>(&mut self) -> &mut Self {
+        self.world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<T>();
+        self
+    }
+
+    #[must_use]
+    pub const fn float_policy(&self) -> FloatPolicy {
+        self.float_policy
+    }
+
+    /// Sets what happens when a tick registers a component or resource with floating point
+    /// fields. Only checked with debug assertions.
+    pub fn set_float_policy(&mut self, policy: FloatPolicy) -> &mut Self {
+        self.float_policy = policy;
+        self
+    }
+
+    /// Excludes `T` from the float audit, e.g. a type that can't derive [`Reflect`] but is known
+    /// to be deterministic.
+    pub fn skip_float_audit<T: 'static>(&mut self) -> &mut Self {
+        self.float_audit_skipped.insert(TypeId::of::<T>());
+        self
+    }
+
+    /// Returns the floating point fields of every component and resource registered in this
+    /// world, regardless of the [`FloatPolicy`].
+    ///
+    /// Only types registered in the world's [`AppTypeRegistry`] can be inspected, see
+    /// [`Subworld::register_type`]. The others are returned with a `None` type, unless they are
+    /// zero-sized or skipped with [`Subworld::skip_float_audit`].
+    #[must_use]
+    pub fn audit_floats(&self) -> Vec<FloatField> {
+        let registry = self.world.resource::<AppTypeRegistry>().read();
+        self.world
+            .components()
+            .iter_registered()
+            .filter(|info| !self.is_float_audit_skipped(info))
+            .flat_map(|info| find_float_fields(&registry, info))
+            .collect()
+    }
+
+    fn is_float_audit_skipped(&self, info: &ComponentInfo) -> bool {
+        info.type_id()
+            .is_none_or(|type_id| self.float_audit_skipped.contains(&type_id))
+    }
+
+    /// Audits the components and resources registered since the last call.
+    #[cfg(debug_assertions)]
+    fn audit_new_components(&mut self) {
+        if self.float_policy == FloatPolicy::Ignore
+            || self.audited_components.len() == self.world.components().num_registered()
+        {
+            return;
+        }
+
+        let registry = self.world.resource::<AppTypeRegistry>().read();
+        let mut found = Vec::new();
+        for info in self.world.components().iter_registered() {
+            if self.audited_components.insert(info.id()) && !self.is_float_audit_skipped(info) {
+                found.extend(find_float_fields(&registry, info));
+            }
+        }
+        drop(registry);
+
+        if found.is_empty() {
+            return;
+        }
+        match self.float_policy {
+            FloatPolicy::Ignore => {}
+            FloatPolicy::Warn => {
+                for field in &found {
+                    warn!("Floating point field in a Subworld can cause desyncs: {field}");
+                }
+            }
+            FloatPolicy::Panic => {
+                let mut report = String::new();
+                for field in &found {
+                    let _ = writeln!(report, "  {field}");
+                }
+                panic!(
+                    "{} floating point fields in Subworld components and resources:\n{report}",
+                    found.len()
+                );
+            }
+        }
+    }
+
+    /// Runs `system` once on this world, outside of any schedule.
+    pub fn run_system_once<O: 'static, M>(
+        &mut self,
+        system: impl IntoSystem<(), O, M>,
+    ) -> Result<O, RunSystemError> {
+        self.world.run_system_once(system)
+    }
+
+    /// Adds a [`DMessages`] queue for `M`, cleared before every tick.
+    pub fn add_message<M: Clone + Send + Sync + 'static>(&mut self) -> &mut Self {
+        if !self.message_queues.contains_key(&TypeId::of::<M>()) {
+            self.init_resource::<DMessages<M>>();
+            self.register_snapshot_resource::<DMessages<M>>();
+            // Messages don't outlive the tick, whatever they write into the state is audited.
+            self.skip_float_audit::<DMessages<M>>();
+            self.message_queues
+                .insert(TypeId::of::<M>(), DMessages::<M>::start_tick);
+        }
+        self
+    }
+
+    /// Registers `C` to have its [`Entity`](bevy::ecs::entity::Entity) references remapped
+    /// by [`Subworld::map_entities`].
+    pub fn register_entity_mapping<C: Component<Mutability = Mutable>>(&mut self) -> &mut Self {
+        self.entity_mappings
+            .insert(TypeId::of::<C>(), map_component_entities::<C>);
+        self
+    }
+
+    /// Remaps the entity references of every registered component, e.g. with
+    /// [`StableIds::entity_map_from`] after the world's entities were recreated.
+    pub fn map_entities(&mut self, mut mapper: impl EntityMapper) {
+        for map in self.entity_mappings.values() {
+            map(&mut self.world, &mut mapper);
+        }
+    }
+
+    /// Registers `C` to be included in [`Subworld::snapshot`] and restored by
+    /// [`Subworld::restore`].
+    pub fn register_snapshot<C: Component + Snapshot>(&mut self) -> &mut Self {
+        self.snapshots
+            .insert(TypeId::of::<C>(), SnapshotFns::of::<C>());
+        self
+    }
+
+    /// Registers `R` to be cloned into [`Subworld::snapshot`] and restored by
+    /// [`Subworld::restore`], which also copies it into forks.
+    pub fn register_snapshot_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.snapshot_resources
+            .insert(TypeId::of::<R>(), SnapshotResourceFns::of::<R>());
+        self
+    }
+
+    /// Registers `C` to be hashed by [`Subworld::checksum`].
+    pub fn register_checksum<C: Component + Checksum>(&mut self) -> &mut Self {
+        self.checksums
+            .insert(TypeId::of::<C>(), checksum_component::<C>);
+        self
+    }
+
     pub fn sync(&mut self, rhs: &mut World, mut f: impl FnMut(&mut World, &mut World)) {
         f(&mut self.world, rhs);
     }
 
     pub fn tick(&mut self, input: Vec<I>) {
+        self.cleanup();
+        // Apply commands queued from outside (e.g. `MultiworldCommands`) before the tick starts.
+        self.world.flush();
+        self.tick_start = self.world.read_change_tick();
         self.world
             .get_resource_mut::<FrameInput<I>>()
             .unwrap()
             .set(input);
 
+        let tick = *self.world.resource::<SimTick>();
+        for start_tick in self.message_queues.values() {
+            start_tick(&mut self.world, tick);
+        }
+
         self.world.run_schedule(FixedSchedule);
+        self.world.resource_mut::<SimTick>().advance();
+        ScheduledCommands::set_tick(&mut self.world);
+
+        #[cfg(debug_assertions)]
+        self.audit_new_components();
     }
 }
 
+/// Built schedules move their systems out of the graph, so look in the executable first.
+fn system_name(schedule: &Schedule, key: SystemKey) -> String {
+    schedule
+        .systems()
+        .ok()
+        .and_then(|mut systems| systems.find(|(id, _)| *id == key))
+        .map_or_else(
+            || schedule.graph().get_node_name(&NodeId::System(key)),
+            |(_, system)| system.name().shortname().to_string(),
+        )
+}
+
 impl<I: UserInput> Deref for Subworld<I> {
     type Target = World;
 
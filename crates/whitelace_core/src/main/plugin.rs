@@ -0,0 +1,108 @@
+use core::any::Any;
+
+use bevy::platform::prelude::{Box, String, ToString, Vec};
+
+use crate::main::{Subworld, input::UserInput};
+
+/// A deterministic plugin, installed directly into a [`Subworld`].
+///
+/// Mirrors bevy's `Plugin`: [`DPlugin::build`] runs when the plugin is added, while
+/// [`DPlugin::finish`] and [`DPlugin::cleanup`] run once before the first tick.
+pub trait DPlugin<I: UserInput>: Any + Send + Sync {
+    fn build(&self, world: &mut Subworld<I>);
+
+    /// Runs after every plugin has been built.
+    fn finish(&self, _world: &mut Subworld<I>) {}
+
+    /// Runs after [`DPlugin::finish`] has been called on every plugin.
+    fn cleanup(&self, _world: &mut Subworld<I>) {}
+
+    /// Configures a name for the plugin, used to detect duplicates.
+    fn name(&self) -> &str {
+        core::any::type_name::<Self>()
+    }
+
+    /// If the plugin can be added to the same world only once.
+    fn is_unique(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DPluginsState {
+    /// Plugins are being added.
+    #[default]
+    Adding,
+    /// [`DPlugin::finish`] has been called on every plugin.
+    Finished,
+    /// [`DPlugin::cleanup`] has been called on every plugin.
+    Cleaned,
+}
+
+/// Combines multiple [`DPlugin`]s into a single unit.
+pub trait DPluginGroup<I: UserInput>: Sized {
+    fn build(self) -> DPluginGroupBuilder<I>;
+
+    fn name() -> String {
+        core::any::type_name::<Self>().to_string()
+    }
+}
+
+/// An ordered list of [`DPlugin`]s, built by a [`DPluginGroup`].
+pub struct DPluginGroupBuilder<I: UserInput> {
+    group_name: String,
+    plugins: Vec<Box<dyn DPlugin<I>>>,
+}
+
+impl<I: UserInput> DPluginGroupBuilder<I> {
+    #[must_use]
+    pub fn start<PG: DPluginGroup<I>>() -> Self {
+        Self {
+            group_name: PG::name(),
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Appends `plugin` to the group, replacing an existing plugin of the same type.
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<T: DPlugin<I>>(mut self, plugin: T) -> Self {
+        self.plugins
+            .retain(|plugin| !(&**plugin as &dyn Any).is::<T>());
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    /// Removes the plugin of type `T` from the group.
+    #[must_use]
+    pub fn disable<T: DPlugin<I>>(mut self) -> Self {
+        self.plugins
+            .retain(|plugin| !(&**plugin as &dyn Any).is::<T>());
+        self
+    }
+
+    #[must_use]
+    pub fn group_name(&self) -> &str {
+        &self.group_name
+    }
+
+    /// Adds every plugin of the group to `world`, in order.
+    ///
+    /// Unique plugins that are already in the world, e.g. pulled in as a dependency by an
+    /// earlier plugin, are skipped.
+    pub fn finish(self, world: &mut Subworld<I>) {
+        for plugin in self.plugins {
+            if plugin.is_unique() && world.is_plugin_name_added(plugin.name()) {
+                continue;
+            }
+            world.add_boxed_plugin(plugin);
+        }
+    }
+}
+
+/// Lets a group edited with [`DPluginGroupBuilder::disable`] be added like any other group.
+impl<I: UserInput> DPluginGroup<I> for DPluginGroupBuilder<I> {
+    fn build(self) -> DPluginGroupBuilder<I> {
+        self
+    }
+}
@@ -0,0 +1,482 @@
+use core::fmt;
+
+use bevy::{
+    ecs::{
+        entity::{Entity, EntityHashMap},
+        reflect::{AppTypeRegistry, ReflectComponent, ReflectResource},
+        relationship::RelationshipHookMode,
+        world::World,
+    },
+    platform::prelude::{Box, String, Vec},
+    reflect::{
+        PartialReflect, Reflect, ReflectFromReflect, TypeRegistration, TypeRegistry,
+        serde::{TypeRegistrationDeserializer, TypedReflectDeserializer, TypedReflectSerializer},
+    },
+};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{DeserializeSeed, Error as _, MapAccess, SeqAccess, Visitor},
+    ser::{Error as _, SerializeMap, SerializeStruct},
+};
+
+use crate::main::{
+    ScheduledCommands, StableIds, Subworld,
+    input::UserInput,
+    snapshot::{mark_component_unchanged, mark_resource_unchanged},
+};
+
+const SUBWORLD_STRUCT: &str = "Subworld";
+const SUBWORLD_FIELDS: &[&str] = &["last_stable_id", "resources", "entities"];
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum SubworldField {
+    LastStableId,
+    Resources,
+    Entities,
+}
+
+/// The entities and resources of a save, read with [`Subworld::deserialize_save`] and applied
+/// with [`Subworld::load`].
+pub struct SavedSubworld {
+    last_stable_id: u64,
+    resources: Vec<Box<dyn PartialReflect>>,
+    entities: Vec<SavedEntity>,
+}
+
+struct SavedEntity {
+    entity: Entity,
+    components: Vec<Box<dyn PartialReflect>>,
+}
+
+impl<I: UserInput> Subworld<I> {
+    /// Serializes the world with any serde format, see [`SubworldSerializer`].
+    #[must_use]
+    pub fn serializer(&self) -> SubworldSerializer<'_> {
+        SubworldSerializer { world: &self.world }
+    }
+
+    /// Reads a save written by [`Subworld::serializer`], using the types registered in this world.
+    pub fn deserialize_save<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<SavedSubworld, D::Error> {
+        let registry = self.world.resource::<AppTypeRegistry>().read();
+        SubworldDeserializer {
+            registry: &registry,
+        }
+        .deserialize(deserializer)
+    }
+
+    /// Replaces every saved entity of this world with the ones of `saved`, and overwrites the
+    /// saved resources.
+    ///
+    /// Entity references in components are remapped to the new entities. Relationships like
+    /// [`ChildOf`](bevy::ecs::hierarchy::ChildOf) are restored as they were saved, without
+    /// running their hooks, so both sides of a relationship have to be registered. Loaded
+    /// components and resources are not reported as added or changed to systems.
+    pub fn load(&mut self, saved: &SavedSubworld) {
+        let registry = self.world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        for entity in saved_entities(&self.world, &registry) {
+            // Might have been despawned along with a related entity already.
+            let _ = self.world.try_despawn(entity);
+        }
+
+        // Ids allocated for required components before their saved `StableId` is inserted
+        // must not collide with the saved ones.
+        let mut stable_ids = self.world.resource_mut::<StableIds>();
+        let last = stable_ids.last().max(saved.last_stable_id);
+        stable_ids.set_last(last);
+
+        let mut entity_map = EntityHashMap::default();
+        for saved_entity in &saved.entities {
+            entity_map.insert(saved_entity.entity, self.world.spawn_empty().id());
+        }
+        let mut loaded = Vec::new();
+        for saved_entity in &saved.entities {
+            let entity = entity_map[&saved_entity.entity];
+            for component in &saved_entity.components {
+                let registration = registration_of(&registry, &**component);
+                loaded.push((entity, registration.type_id()));
+                registration
+                    .data::<ReflectComponent>()
+                    .unwrap()
+                    .apply_or_insert_mapped(
+                        &mut self.world.entity_mut(entity),
+                        component.as_partial_reflect(),
+                        &registry,
+                        &mut entity_map,
+                        RelationshipHookMode::Skip,
+                    );
+            }
+        }
+
+        for resource in &saved.resources {
+            registration_of(&registry, &**resource)
+                .data::<ReflectResource>()
+                .unwrap()
+                .apply_or_insert(&mut self.world, resource.as_partial_reflect(), &registry);
+        }
+
+        // The world continues from the saved state, so systems must not see what was loaded as
+        // added or changed, e.g. the tilemap would split its chunks again.
+        for (entity, type_id) in loaded {
+            if let Some(id) = self.world.components().get_id(type_id) {
+                mark_component_unchanged(&mut self.world, entity, id);
+            }
+        }
+        for resource in &saved.resources {
+            let type_id = registration_of(&registry, &**resource).type_id();
+            if let Some(id) = self.world.components().get_resource_id(type_id) {
+                mark_resource_unchanged(&mut self.world, id);
+            }
+        }
+
+        self.world
+            .resource_mut::<StableIds>()
+            .set_last(saved.last_stable_id);
+        self.world.flush();
+    }
+
+    /// Writes the world as RON. `Fx` values are written as their raw bits, so they load back
+    /// exactly.
+    ///
+    /// Fails while [`ScheduledCommands`] are pending, see [`SubworldSerializer`].
+    pub fn save_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(&self.serializer(), ron::ser::PrettyConfig::default())
+    }
+
+    /// Loads a world written by [`Subworld::save_ron`], see [`Subworld::load`].
+    pub fn load_ron(&mut self, ron: &str) -> Result<(), ron::error::SpannedError> {
+        let mut deserializer = ron::de::Deserializer::from_str(ron)?;
+        let saved = self
+            .deserialize_save(&mut deserializer)
+            .map_err(|error| deserializer.span_error(error))?;
+        deserializer
+            .end()
+            .map_err(|error| deserializer.span_error(error))?;
+        self.load(&saved);
+        Ok(())
+    }
+}
+
+/// Serializes every component and resource of a [`Subworld`] whose type is registered in its
+/// [`AppTypeRegistry`] with [`ReflectComponent`] or [`ReflectResource`]. Entities without any of
+/// those components are skipped.
+///
+/// Entities, components and resources are written in a stable order, so saving the same state
+/// twice gives the same output.
+///
+/// [`ScheduledCommands`] are closures, which can't be written, so serializing fails while any
+/// command is pending. Save between ticks once they ran, or cancel them first.
+pub struct SubworldSerializer<'a> {
+    world: &'a World,
+}
+
+impl Serialize for SubworldSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let Some(commands) = self.world.get_resource::<ScheduledCommands>()
+            && !commands.is_empty()
+        {
+            return Err(S::Error::custom(format_args!(
+                "can't save a Subworld with {} pending ScheduledCommands",
+                commands.len()
+            )));
+        }
+
+        let registry = self.world.resource::<AppTypeRegistry>().read();
+        let registry = &*registry;
+
+        let mut state = serializer.serialize_struct(SUBWORLD_STRUCT, SUBWORLD_FIELDS.len())?;
+        state.serialize_field("last_stable_id", &self.world.resource::<StableIds>().last())?;
+        state.serialize_field(
+            "resources",
+            &ReflectedMapSerializer {
+                entries: saved_resources(self.world, registry),
+                registry,
+            },
+        )?;
+        state.serialize_field(
+            "entities",
+            &EntitiesSerializer {
+                world: self.world,
+                registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+struct EntitiesSerializer<'a> {
+    world: &'a World,
+    registry: &'a TypeRegistry,
+}
+
+impl Serialize for EntitiesSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let entities = saved_entities(self.world, self.registry);
+        let mut state = serializer.serialize_map(Some(entities.len()))?;
+        for entity in entities {
+            state.serialize_entry(
+                &entity.to_bits(),
+                &ReflectedMapSerializer {
+                    entries: saved_components(self.world, self.registry, entity),
+                    registry: self.registry,
+                },
+            )?;
+        }
+        state.end()
+    }
+}
+
+/// Reflected values keyed by their type path.
+struct ReflectedMapSerializer<'a> {
+    entries: Vec<(&'static str, &'a dyn Reflect)>,
+    registry: &'a TypeRegistry,
+}
+
+impl Serialize for ReflectedMapSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_map(Some(self.entries.len()))?;
+        for (type_path, value) in &self.entries {
+            state.serialize_entry(
+                type_path,
+                &TypedReflectSerializer::new(value.as_partial_reflect(), self.registry),
+            )?;
+        }
+        state.end()
+    }
+}
+
+fn saved_resources<'w>(
+    world: &'w World,
+    registry: &TypeRegistry,
+) -> Vec<(&'static str, &'w dyn Reflect)> {
+    let mut resources: Vec<_> = registry
+        .iter()
+        .filter_map(|registration| {
+            let value = registration
+                .data::<ReflectResource>()?
+                .reflect(world)
+                .ok()?;
+            Some((registration.type_info().type_path(), value))
+        })
+        .collect();
+    resources.sort_by_key(|(type_path, _)| *type_path);
+    resources
+}
+
+fn saved_components<'w>(
+    world: &'w World,
+    registry: &TypeRegistry,
+    entity: Entity,
+) -> Vec<(&'static str, &'w dyn Reflect)> {
+    let entity = world.entity(entity);
+    let mut components: Vec<_> = entity
+        .archetype()
+        .iter_components()
+        .filter_map(|id| {
+            let type_id = world.components().get_info(id)?.type_id()?;
+            let registration = registry.get(type_id)?;
+            let value = registration.data::<ReflectComponent>()?.reflect(entity)?;
+            Some((registration.type_info().type_path(), value))
+        })
+        .collect();
+    components.sort_by_key(|(type_path, _)| *type_path);
+    components
+}
+
+/// Entities with at least one registered component, in the order of their ids.
+fn saved_entities(world: &World, registry: &TypeRegistry) -> Vec<Entity> {
+    let mut entities: Vec<_> = world
+        .archetypes()
+        .iter()
+        .filter(|archetype| {
+            archetype.iter_components().any(|id| {
+                world
+                    .components()
+                    .get_info(id)
+                    .and_then(|info| registry.get(info.type_id()?))
+                    .is_some_and(|registration| registration.data::<ReflectComponent>().is_some())
+            })
+        })
+        .flat_map(|archetype| archetype.entities().iter().map(|entity| entity.id()))
+        .collect();
+    entities.sort();
+    entities
+}
+
+fn registration_of<'a>(
+    registry: &'a TypeRegistry,
+    value: &dyn PartialReflect,
+) -> &'a TypeRegistration {
+    // Only registered types can be deserialized, see `ReflectedMapDeserializer`.
+    registry
+        .get(value.get_represented_type_info().unwrap().type_id())
+        .unwrap()
+}
+
+/// Reads a save written by [`SubworldSerializer`] into a [`SavedSubworld`].
+pub struct SubworldDeserializer<'a> {
+    pub registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for SubworldDeserializer<'_> {
+    type Value = SavedSubworld;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct(SUBWORLD_STRUCT, SUBWORLD_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for SubworldDeserializer<'_> {
+    type Value = SavedSubworld;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a saved Subworld")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let last_stable_id = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let resources = seq
+            .next_element_seed(ReflectedMapDeserializer::resources(self.registry))?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let entities = seq
+            .next_element_seed(EntitiesDeserializer(self.registry))?
+            .ok_or_else(|| A::Error::invalid_length(2, &self))?;
+        Ok(SavedSubworld {
+            last_stable_id,
+            resources,
+            entities,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut last_stable_id = None;
+        let mut resources = None;
+        let mut entities = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                SubworldField::LastStableId => last_stable_id = Some(map.next_value()?),
+                SubworldField::Resources => {
+                    resources = Some(
+                        map.next_value_seed(ReflectedMapDeserializer::resources(self.registry))?,
+                    );
+                }
+                SubworldField::Entities => {
+                    entities = Some(map.next_value_seed(EntitiesDeserializer(self.registry))?);
+                }
+            }
+        }
+        Ok(SavedSubworld {
+            last_stable_id: last_stable_id
+                .ok_or_else(|| A::Error::missing_field("last_stable_id"))?,
+            resources: resources.ok_or_else(|| A::Error::missing_field("resources"))?,
+            entities: entities.ok_or_else(|| A::Error::missing_field("entities"))?,
+        })
+    }
+}
+
+struct EntitiesDeserializer<'a>(&'a TypeRegistry);
+
+impl<'de> DeserializeSeed<'de> for EntitiesDeserializer<'_> {
+    type Value = Vec<SavedEntity>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntitiesDeserializer<'_> {
+    type Value = Vec<SavedEntity>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of entities")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entities = Vec::new();
+        while let Some(bits) = map.next_key::<u64>()? {
+            let entity = Entity::try_from_bits(bits)
+                .ok_or_else(|| A::Error::custom(format_args!("invalid entity bits {bits}")))?;
+            let components = map.next_value_seed(ReflectedMapDeserializer::components(self.0))?;
+            entities.push(SavedEntity { entity, components });
+        }
+        Ok(entities)
+    }
+}
+
+struct ReflectedMapDeserializer<'a> {
+    registry: &'a TypeRegistry,
+    resources: bool,
+}
+
+impl<'a> ReflectedMapDeserializer<'a> {
+    const fn resources(registry: &'a TypeRegistry) -> Self {
+        Self {
+            registry,
+            resources: true,
+        }
+    }
+
+    const fn components(registry: &'a TypeRegistry) -> Self {
+        Self {
+            registry,
+            resources: false,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ReflectedMapDeserializer<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ReflectedMapDeserializer<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of reflected values keyed by type path")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::new();
+        while let Some(registration) =
+            map.next_key_seed(TypeRegistrationDeserializer::new(self.registry))?
+        {
+            let registered = if self.resources {
+                registration.data::<ReflectResource>().is_some()
+            } else {
+                registration.data::<ReflectComponent>().is_some()
+            };
+            if !registered {
+                return Err(A::Error::custom(format_args!(
+                    "{} is not registered as a {}",
+                    registration.type_info().type_path(),
+                    if self.resources {
+                        "resource"
+                    } else {
+                        "component"
+                    },
+                )));
+            }
+
+            let value =
+                map.next_value_seed(TypedReflectDeserializer::new(registration, self.registry))?;
+            // Turn dynamic values into the concrete type, as the hooks of the type expect.
+            let value = registration
+                .data::<ReflectFromReflect>()
+                .and_then(|from_reflect| from_reflect.from_reflect(value.as_partial_reflect()))
+                .map_or(value, PartialReflect::into_partial_reflect);
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
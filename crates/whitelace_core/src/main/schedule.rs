@@ -1,33 +1,160 @@
 use bevy::{
-    ecs::schedule::{ExecutorKind, InternedScheduleLabel, Schedule, ScheduleLabel},
+    ecs::schedule::{
+        ExecutorKind, InternedScheduleLabel, NodeId, Schedule, ScheduleBuildError,
+        ScheduleBuildPass, ScheduleGraph, ScheduleLabel, SystemKey, SystemSetKey,
+        graph::{Dag, DiGraph},
+    },
+    platform::hash::FixedHasher,
     prelude::*,
 };
+// Required by the signature of `ScheduleBuildPass::collapse_set`.
+#[allow(clippy::disallowed_types)]
+use indexmap::IndexSet;
 
-use crate::main::{DPlugin, Subworld, input::UserInput};
+use crate::main::{
+    DPlugin, ScheduledCommands, Subworld, ambiguity::AmbiguityPolicy, input::UserInput,
+};
 
+/// Defines the schedules run by [`FixedSchedule`] on every tick, in order.
+///
+/// The default order is `FixedFirst`, `Physics`, `PreFixedUpdate`, `FixedUpdate`,
+/// `PostFixedUpdate`. Note that [`Physics`] runs before transforms are propagated in
+/// [`PreFixedUpdate`].
 #[derive(Resource, Debug)]
-struct ScheduleOrder {
-    labels: Vec<InternedScheduleLabel>,
+pub struct ScheduleOrder {
+    /// The labels to run for each tick, in the order they will be run.
+    pub labels: Vec<InternedScheduleLabel>,
+    /// The labels to run once before the first tick, in the order they will be run.
+    pub startup_labels: Vec<InternedScheduleLabel>,
 }
 
 impl Default for ScheduleOrder {
     fn default() -> Self {
         Self {
             labels: vec![
+                FixedFirst.intern(),
                 Physics.intern(),
                 PreFixedUpdate.intern(),
                 FixedUpdate.intern(),
                 PostFixedUpdate.intern(),
             ],
+            startup_labels: vec![Startup.intern()],
         }
     }
 }
 
+impl ScheduleOrder {
+    /// Inserts `schedule` right after `after`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `after` is not in the order.
+    pub fn insert_after(&mut self, after: impl ScheduleLabel, schedule: impl ScheduleLabel) {
+        let index = index_of(&self.labels, &after);
+        self.labels.insert(index + 1, schedule.intern());
+    }
+
+    /// Inserts `schedule` right before `before`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `before` is not in the order.
+    pub fn insert_before(&mut self, before: impl ScheduleLabel, schedule: impl ScheduleLabel) {
+        let index = index_of(&self.labels, &before);
+        self.labels.insert(index, schedule.intern());
+    }
+
+    /// Inserts the startup `schedule` right after `after`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `after` is not in the startup order.
+    pub fn insert_startup_after(
+        &mut self,
+        after: impl ScheduleLabel,
+        schedule: impl ScheduleLabel,
+    ) {
+        let index = index_of(&self.startup_labels, &after);
+        self.startup_labels.insert(index + 1, schedule.intern());
+    }
+
+    /// Inserts the startup `schedule` right before `before`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `before` is not in the startup order.
+    pub fn insert_startup_before(
+        &mut self,
+        before: impl ScheduleLabel,
+        schedule: impl ScheduleLabel,
+    ) {
+        let index = index_of(&self.startup_labels, &before);
+        self.startup_labels.insert(index, schedule.intern());
+    }
+
+    /// Appends `schedule` to the end of the order.
+    pub fn push(&mut self, schedule: impl ScheduleLabel) {
+        self.labels.push(schedule.intern());
+    }
+
+    /// Removes `schedule` from the order. Returns `false` if it was not present.
+    pub fn remove(&mut self, schedule: impl ScheduleLabel) -> bool {
+        let schedule = schedule.intern();
+        let len = self.labels.len();
+        self.labels.retain(|label| *label != schedule);
+        self.labels.len() != len
+    }
+
+    #[must_use]
+    pub fn contains(&self, schedule: impl ScheduleLabel) -> bool {
+        self.labels.contains(&schedule.intern())
+    }
+
+    /// Appends the startup `schedule` to the end of the startup order.
+    pub fn push_startup(&mut self, schedule: impl ScheduleLabel) {
+        self.startup_labels.push(schedule.intern());
+    }
+
+    /// Removes the startup `schedule` from the startup order. Returns `false` if it was not
+    /// present.
+    pub fn remove_startup(&mut self, schedule: impl ScheduleLabel) -> bool {
+        let schedule = schedule.intern();
+        let len = self.startup_labels.len();
+        self.startup_labels.retain(|label| *label != schedule);
+        self.startup_labels.len() != len
+    }
+
+    #[must_use]
+    pub fn contains_startup(&self, schedule: impl ScheduleLabel) -> bool {
+        self.startup_labels.contains(&schedule.intern())
+    }
+}
+
+fn index_of(labels: &[InternedScheduleLabel], schedule: &impl ScheduleLabel) -> usize {
+    let interned = schedule.intern();
+    labels
+        .iter()
+        .position(|label| *label == interned)
+        .unwrap_or_else(|| panic!("Expected {schedule:?} to be in the ScheduleOrder"))
+}
+
 #[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
 pub(crate) struct FixedSchedule;
 
 impl FixedSchedule {
     pub fn run(world: &mut World) {
+        // Based on the tick rather than on this system, so forks and worlds restored to a
+        // later tick don't run the startup schedules again.
+        if world.resource::<SimTick>().get() == 0 {
+            world.resource_scope(|world, order: Mut<ScheduleOrder>| {
+                for &label in &order.startup_labels {
+                    let _ = world.try_run_schedule(label);
+                }
+            });
+        }
+
+        ScheduledCommands::run_due(world);
+
         world.resource_scope(|world, order: Mut<ScheduleOrder>| {
             for &label in &order.labels {
                 let _ = world.try_run_schedule(label);
@@ -36,6 +163,37 @@ impl FixedSchedule {
     }
 }
 
+/// The index of the current tick of the [`Subworld`], starting at 0.
+///
+/// Advanced after every tick, so between ticks it is the number of completed ticks.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[reflect(Resource, Default, Debug, PartialEq, Hash)]
+pub struct SimTick(u64);
+
+impl SimTick {
+    #[must_use]
+    pub const fn new(tick: u64) -> Self {
+        Self(tick)
+    }
+
+    #[must_use]
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    pub(crate) const fn advance(&mut self) {
+        self.0 += 1;
+    }
+}
+
+/// Runs once, before the first tick of the [`Subworld`].
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct Startup;
+
+/// Runs first on every tick, e.g. to remember the state the tick started from.
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct FixedFirst;
+
 #[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct Physics;
 
@@ -48,12 +206,109 @@ pub struct FixedUpdate;
 #[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct PostFixedUpdate;
 
+/// How the schedules of a [`Subworld`] are executed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubworldExecutor {
+    /// Runs systems one at a time.
+    #[default]
+    SingleThreaded,
+    /// Runs non-conflicting systems in parallel.
+    ///
+    /// Schedules fail to build if two systems access the same data without an explicit
+    /// ordering. Systems with deferred params such as `Commands` access no data but reserve
+    /// entities from the shared allocator, so they are run one after the other in the order
+    /// they would run single-threaded, and the spawned entities don't depend on which thread
+    /// finishes first either.
+    #[cfg(feature = "multi_threaded")]
+    MultiThreaded,
+}
+
+impl SubworldExecutor {
+    /// The policy actually enforced with this executor.
+    /// Running ambiguous systems in parallel is never deterministic, so it always panics.
+    #[must_use]
+    pub fn effective_policy(self, policy: AmbiguityPolicy) -> AmbiguityPolicy {
+        match self {
+            Self::SingleThreaded => policy,
+            #[cfg(feature = "multi_threaded")]
+            Self::MultiThreaded => AmbiguityPolicy::Panic,
+        }
+    }
+
+    pub(crate) fn configure(self, schedule: &mut Schedule, policy: AmbiguityPolicy) {
+        match self {
+            Self::SingleThreaded => {
+                schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+                schedule.remove_build_pass::<OrderDeferredPass>();
+            }
+            #[cfg(feature = "multi_threaded")]
+            Self::MultiThreaded => {
+                schedule.set_executor_kind(ExecutorKind::MultiThreaded);
+                schedule.add_build_pass(OrderDeferredPass);
+            }
+        }
+
+        let mut settings = schedule.get_build_settings();
+        settings.ambiguity_detection = self.effective_policy(policy).log_level();
+        schedule.set_build_settings(settings);
+    }
+}
+
+/// Chains the systems with deferred params in their topological order, which is the order the
+/// single-threaded executor runs them in.
+///
+/// Added after bevy's pass that inserts the `ApplyDeferred` sync points, so the new edges don't
+/// add sync points, and can't form a cycle since they follow the existing order.
+#[derive(Debug)]
+struct OrderDeferredPass;
+
+impl ScheduleBuildPass for OrderDeferredPass {
+    type EdgeOptions = ();
+
+    fn add_dependency(&mut self, _from: NodeId, _to: NodeId, _options: Option<&Self::EdgeOptions>) {
+    }
+
+    #[allow(clippy::disallowed_types)]
+    fn collapse_set(
+        &mut self,
+        _set: SystemSetKey,
+        _systems: &IndexSet<SystemKey, FixedHasher>,
+        _dependency_flattening: &DiGraph<NodeId>,
+    ) -> impl Iterator<Item = (NodeId, NodeId)> {
+        core::iter::empty()
+    }
+
+    fn build(
+        &mut self,
+        _world: &mut World,
+        graph: &mut ScheduleGraph,
+        dependency_flattened: &mut Dag<SystemKey>,
+    ) -> Result<(), ScheduleBuildError> {
+        let deferred: Vec<SystemKey> = dependency_flattened
+            .toposort()
+            .map_err(ScheduleBuildError::FlatDependencySort)?
+            .iter()
+            .copied()
+            .filter(|&key| graph.systems[key].has_deferred())
+            .collect();
+        for pair in deferred.windows(2) {
+            dependency_flattened.graph_mut().add_edge(pair[0], pair[1]);
+        }
+        Ok(())
+    }
+}
+
 pub struct SchedulePlugin;
 impl<I: UserInput> DPlugin<I> for SchedulePlugin {
     fn build(&self, app: &mut Subworld<I>) {
         let mut fixed_schedule = Schedule::new(FixedSchedule);
         fixed_schedule.set_executor_kind(ExecutorKind::SingleThreaded);
         app.init_resource::<ScheduleOrder>();
+        app.init_resource::<SimTick>();
+        app.init_resource::<ScheduledCommands>();
+        app.register_snapshot_resource::<ScheduledCommands>();
+        app.skip_float_audit::<ScheduleOrder>()
+            .skip_float_audit::<ScheduledCommands>();
         app.add_schedule(fixed_schedule);
         app.add_systems(FixedSchedule, FixedSchedule::run);
     }
@@ -0,0 +1,591 @@
+use core::{
+    any::{Any, TypeId},
+    fmt,
+};
+
+use bevy::{
+    ecs::{
+        change_detection::{ComponentTicks, DetectChangesMut, MAX_CHANGE_AGE, Tick},
+        component::{Component, ComponentId},
+        entity::{Entity, EntityHashMap},
+        hierarchy::ChildOf,
+        resource::Resource,
+        world::{EntityWorldMut, World},
+    },
+    platform::{prelude::Vec, sync::Arc},
+    state::state::{State, States},
+};
+
+use crate::{
+    main::{StableId, StableIds, Subworld, input::UserInput, schedule::SimTick},
+    map::{Map, Set},
+    math::{FQuat, FVec3, Fx},
+};
+
+/// Exact binary encoding of a component for [`WorldSnapshot`]s, usually derived with
+/// `#[derive(Snapshot)]`. Components are only part of snapshots once registered with
+/// [`Subworld::register_snapshot`], which the derive doesn't do.
+///
+/// `Fx` values are written as their raw bits, so a restored world continues exactly as the
+/// original one would.
+pub trait Snapshot: Sized {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter);
+
+    /// # Panics
+    ///
+    /// Panics if the data wasn't written by [`Snapshot::write_snapshot`] of the same type.
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self;
+}
+
+#[derive(Default)]
+pub struct SnapshotWriter {
+    bytes: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+}
+
+pub struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+    entities: &'a EntityHashMap<Entity>,
+}
+
+impl SnapshotReader<'_> {
+    /// # Panics
+    ///
+    /// Panics if less than `N` bytes are left.
+    pub fn read_bytes<const N: usize>(&mut self) -> [u8; N] {
+        let (bytes, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .expect("snapshot data ended early");
+        self.bytes = rest;
+        *bytes
+    }
+
+    /// Maps an entity of the snapshot to the one that replaced it when the snapshot was
+    /// restored, see [`Subworld::restore`].
+    #[must_use]
+    pub fn map_entity(&self, entity: Entity) -> Entity {
+        self.entities.get(&entity).copied().unwrap_or(entity)
+    }
+}
+
+/// The registered components of every entity with a [`StableId`] and the registered resources,
+/// taken with [`Subworld::snapshot`].
+#[derive(Debug, Clone)]
+pub struct WorldSnapshot {
+    tick: SimTick,
+    last_stable_id: u64,
+    entities: Vec<SnapshotEntity>,
+    resources: Vec<(TypeId, SnapshotResourceFns, SnapshotResource, bool)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SnapshotEntity {
+    id: StableId,
+    entity: Entity,
+    /// The encoded components and whether they changed during the tick before the snapshot.
+    components: Vec<(TypeId, Vec<u8>, bool)>,
+}
+
+impl WorldSnapshot {
+    /// The tick the world was at when the snapshot was taken.
+    #[must_use]
+    pub const fn tick(&self) -> SimTick {
+        self.tick
+    }
+
+    /// The number of entities in the snapshot.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// A clone of a resource, shared between the clones of the snapshot.
+#[derive(Clone)]
+struct SnapshotResource(Arc<dyn Any + Send + Sync>);
+
+impl fmt::Debug for SnapshotResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SnapshotResource").finish_non_exhaustive()
+    }
+}
+
+/// Whether a component or resource was changed since the last tick started.
+fn changed_since(ticks: Option<ComponentTicks>, tick_start: Tick, world: &World) -> bool {
+    ticks.is_some_and(|ticks| ticks.is_changed(tick_start, world.read_change_tick()))
+}
+
+/// Marks a restored component as changed now, or as old enough that no system sees it as added
+/// or changed. Restored components are never marked as added, since the systems reacting to
+/// them already ran in the tick they were added in.
+fn mark_component(world: &mut World, entity: Entity, id: ComponentId, changed: bool) {
+    let (added, changed) = restored_ticks(world, changed);
+    // Immutable components can't be marked.
+    if let Ok(mut component) = world.entity_mut(entity).get_mut_by_id(id) {
+        component.set_last_added(added);
+        component.set_last_changed(changed);
+    }
+}
+
+fn mark_resource(world: &mut World, id: ComponentId, changed: bool) {
+    let (added, changed) = restored_ticks(world, changed);
+    if let Some(mut resource) = world.get_resource_mut_by_id(id) {
+        resource.set_last_added(added);
+        resource.set_last_changed(changed);
+    }
+}
+
+fn restored_ticks(world: &World, changed: bool) -> (Tick, Tick) {
+    let now = world.read_change_tick();
+    let unchanged = Tick::new(now.get().wrapping_sub(MAX_CHANGE_AGE));
+    (unchanged, if changed { now } else { unchanged })
+}
+
+/// Hides a loaded component from `Added` and `Changed` filters.
+#[cfg(feature = "serialize")]
+pub(crate) fn mark_component_unchanged(world: &mut World, entity: Entity, id: ComponentId) {
+    mark_component(world, entity, id, false);
+}
+
+/// Hides a loaded resource from change detection.
+#[cfg(feature = "serialize")]
+pub(crate) fn mark_resource_unchanged(world: &mut World, id: ComponentId) {
+    mark_resource(world, id, false);
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SnapshotResourceFns {
+    write: fn(&World) -> Option<SnapshotResource>,
+    read: fn(&mut World, Option<&SnapshotResource>),
+}
+
+impl SnapshotResourceFns {
+    pub(crate) fn of<R: Resource + Clone>() -> Self {
+        Self {
+            write: |world| {
+                let resource = world.get_resource::<R>()?;
+                Some(SnapshotResource(Arc::new(resource.clone())))
+            },
+            read: |world, saved| match saved {
+                Some(saved) => {
+                    let resource = saved.0.downcast_ref::<R>().unwrap();
+                    world.insert_resource(resource.clone());
+                }
+                None => {
+                    world.remove_resource::<R>();
+                }
+            },
+        }
+    }
+
+    /// [`State<S>`] isn't `Clone`, so the state itself is cloned instead.
+    pub(crate) fn of_state<S: States>() -> Self {
+        Self {
+            write: |world| {
+                let state = world.get_resource::<State<S>>()?;
+                Some(SnapshotResource(Arc::new(state.get().clone())))
+            },
+            read: |world, saved| match saved {
+                Some(saved) => {
+                    let state = saved.0.downcast_ref::<S>().unwrap();
+                    world.insert_resource(State::new(state.clone()));
+                }
+                None => {
+                    world.remove_resource::<State<S>>();
+                }
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct SnapshotFns {
+    write: fn(&World, Entity) -> Option<Vec<u8>>,
+    read: fn(&mut EntityWorldMut, &mut SnapshotReader),
+    remove: fn(&mut EntityWorldMut),
+}
+
+impl SnapshotFns {
+    pub(crate) fn of<C: Component + Snapshot>() -> Self {
+        Self {
+            write: |world, entity| {
+                let component = world.get::<C>(entity)?;
+                let mut writer = SnapshotWriter::default();
+                component.write_snapshot(&mut writer);
+                Some(writer.bytes)
+            },
+            read: |entity, reader| {
+                entity.insert(C::read_snapshot(reader));
+            },
+            remove: |entity| {
+                entity.remove::<C>();
+            },
+        }
+    }
+}
+
+impl<I: UserInput> Subworld<I> {
+    /// Takes a snapshot of every entity with a [`StableId`], with the components registered
+    /// with [`Subworld::register_snapshot`], and of the resources registered with
+    /// [`Subworld::register_snapshot_resource`].
+    #[must_use]
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let stable_ids = self.world.resource::<StableIds>();
+        let components = self.world.components();
+        WorldSnapshot {
+            tick: *self.world.resource::<SimTick>(),
+            last_stable_id: stable_ids.last(),
+            entities: stable_ids
+                .iter()
+                .map(|(id, entity)| SnapshotEntity {
+                    id,
+                    entity,
+                    components: self
+                        .snapshots
+                        .iter()
+                        .filter_map(|(type_id, fns)| {
+                            let bytes = (fns.write)(&self.world, entity)?;
+                            let ticks = components.get_id(*type_id).and_then(|id| {
+                                self.world.entity(entity).get_change_ticks_by_id(id)
+                            });
+                            let changed = changed_since(ticks, self.tick_start, &self.world);
+                            Some((*type_id, bytes, changed))
+                        })
+                        .collect(),
+                })
+                .collect(),
+            resources: self
+                .snapshot_resources
+                .iter()
+                .filter_map(|(type_id, fns)| {
+                    let resource = (fns.write)(&self.world)?;
+                    let ticks = components
+                        .get_resource_id(*type_id)
+                        .and_then(|id| self.world.get_resource_change_ticks_by_id(id));
+                    let changed = changed_since(ticks, self.tick_start, &self.world);
+                    Some((*type_id, *fns, resource, changed))
+                })
+                .collect(),
+        }
+    }
+
+    /// Restores the registered components of every entity with a [`StableId`] and the
+    /// registered resources to their state in `snapshot`, e.g. to roll back and resimulate
+    /// ticks. Resources registered in the world `snapshot` was taken from are restored even if
+    /// they aren't registered in this one, and registered resources missing from `snapshot` are
+    /// removed.
+    ///
+    /// Entities spawned since the snapshot are despawned and the despawned ones are spawned
+    /// again, with their `Entity` references in registered components remapped. The world
+    /// continues from the restored state: systems see the restored components and resources
+    /// as changed if they changed during the tick before the snapshot, e.g. so transforms set
+    /// late in that tick are still propagated, and never as added, e.g. so the tilemap doesn't
+    /// give its chunks new colliders. Systems that already saw a change in that tick see it
+    /// again, so systems filtering on `Changed` should give the same result when run twice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `snapshot` has a component that isn't registered in this world.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        assert!(
+            snapshot
+                .entities
+                .iter()
+                .flat_map(|saved| &saved.components)
+                .all(|(type_id, _, _)| self.snapshots.contains_key(type_id)),
+            "snapshot has a component that isn't registered with `register_snapshot`"
+        );
+
+        let kept: Set<StableId> = snapshot.entities.iter().map(|saved| saved.id).collect();
+        let spawned: Vec<_> = self
+            .world
+            .resource::<StableIds>()
+            .iter()
+            .filter(|(id, _)| !kept.contains(id))
+            .map(|(_, entity)| entity)
+            .collect();
+        for entity in spawned {
+            // Might have been despawned along with a related entity already.
+            let _ = self.world.try_despawn(entity);
+        }
+
+        let mut entities = EntityHashMap::default();
+        let mut targets = Vec::with_capacity(snapshot.entities.len());
+        let mut restored = Vec::new();
+        for saved in &snapshot.entities {
+            let entity = match self.world.resource::<StableIds>().entity(saved.id) {
+                Some(entity) => entity,
+                None => {
+                    let entity = self.world.spawn(saved.id).id();
+                    restored.push((entity, TypeId::of::<StableId>(), false));
+                    entity
+                }
+            };
+            if entity != saved.entity {
+                entities.insert(saved.entity, entity);
+            }
+            targets.push(entity);
+        }
+
+        for (saved, entity) in snapshot.entities.iter().zip(targets) {
+            for (type_id, fns) in &self.snapshots {
+                let saved = saved
+                    .components
+                    .iter()
+                    .find(|(saved_type, _, _)| saved_type == type_id);
+                let current = (fns.write)(&self.world, entity);
+                match saved {
+                    Some((_, bytes, changed))
+                        if entities.is_empty() && current.as_ref() == Some(bytes) =>
+                    {
+                        restored.push((entity, *type_id, *changed));
+                    }
+                    Some((_, bytes, changed)) => {
+                        let mut reader = SnapshotReader {
+                            bytes,
+                            entities: &entities,
+                        };
+                        (fns.read)(&mut self.world.entity_mut(entity), &mut reader);
+                        restored.push((entity, *type_id, *changed));
+                    }
+                    None if current.is_some() => (fns.remove)(&mut self.world.entity_mut(entity)),
+                    None => {}
+                }
+            }
+        }
+
+        for (type_id, fns, saved, changed) in &snapshot.resources {
+            (fns.read)(&mut self.world, Some(saved));
+            if let Some(id) = self.world.components().get_resource_id(*type_id) {
+                mark_resource(&mut self.world, id, *changed);
+            }
+        }
+        for (type_id, fns) in &self.snapshot_resources {
+            if !snapshot
+                .resources
+                .iter()
+                .any(|(saved_type, _, _, _)| saved_type == type_id)
+            {
+                (fns.read)(&mut self.world, None);
+            }
+        }
+
+        for (entity, type_id, changed) in restored {
+            // Might have been despawned by a hook of another restored component.
+            if let Some(id) = self.world.components().get_id(type_id)
+                && self.world.entities().contains(entity)
+            {
+                mark_component(&mut self.world, entity, id, changed);
+            }
+        }
+        self.tick_start = Tick::new(self.world.read_change_tick().get().wrapping_sub(1));
+
+        *self.world.resource_mut::<SimTick>() = snapshot.tick;
+        self.world
+            .resource_mut::<StableIds>()
+            .set_last(snapshot.last_stable_id);
+        self.world.flush();
+    }
+}
+
+macro_rules! impl_snapshot_for_int {
+    ($($ty:ty),*) => {$(
+        impl Snapshot for $ty {
+            fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+                writer.write_bytes(&self.to_le_bytes());
+            }
+
+            fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+                Self::from_le_bytes(reader.read_bytes())
+            }
+        }
+    )*};
+}
+
+impl_snapshot_for_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+// Written as 64 bits, so snapshots don't depend on the pointer width.
+impl Snapshot for usize {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        u64::try_from(*self).unwrap().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        Self::try_from(u64::read_snapshot(reader)).unwrap()
+    }
+}
+
+impl Snapshot for isize {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        i64::try_from(*self).unwrap().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        Self::try_from(i64::read_snapshot(reader)).unwrap()
+    }
+}
+
+impl Snapshot for bool {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        u8::from(*self).write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        match u8::read_snapshot(reader) {
+            0 => false,
+            1 => true,
+            value => panic!("invalid snapshot bool {value}"),
+        }
+    }
+}
+
+impl Snapshot for () {
+    fn write_snapshot(&self, _: &mut SnapshotWriter) {}
+
+    fn read_snapshot(_: &mut SnapshotReader) -> Self {}
+}
+
+impl Snapshot for Fx {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.to_bits().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        Self::from_bits(i64::read_snapshot(reader))
+    }
+}
+
+impl Snapshot for FVec3 {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.x.write_snapshot(writer);
+        self.y.write_snapshot(writer);
+        self.z.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        Self {
+            x: Fx::read_snapshot(reader),
+            y: Fx::read_snapshot(reader),
+            z: Fx::read_snapshot(reader),
+        }
+    }
+}
+
+impl Snapshot for FQuat {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.x.write_snapshot(writer);
+        self.y.write_snapshot(writer);
+        self.z.write_snapshot(writer);
+        self.w.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        Self {
+            x: Fx::read_snapshot(reader),
+            y: Fx::read_snapshot(reader),
+            z: Fx::read_snapshot(reader),
+            w: Fx::read_snapshot(reader),
+        }
+    }
+}
+
+impl Snapshot for Entity {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.to_bits().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        let entity = Self::from_bits(u64::read_snapshot(reader));
+        reader.map_entity(entity)
+    }
+}
+
+impl Snapshot for StableId {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.to_raw().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        Self::from_raw(u64::read_snapshot(reader))
+    }
+}
+
+impl Snapshot for ChildOf {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.parent().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        Self(Entity::read_snapshot(reader))
+    }
+}
+
+impl<T: Snapshot> Snapshot for Option<T> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        match self {
+            Some(value) => {
+                true.write_snapshot(writer);
+                value.write_snapshot(writer);
+            }
+            None => false.write_snapshot(writer),
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        bool::read_snapshot(reader).then(|| T::read_snapshot(reader))
+    }
+}
+
+impl<T: Snapshot, const N: usize> Snapshot for [T; N] {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        for value in self {
+            value.write_snapshot(writer);
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        core::array::from_fn(|_| T::read_snapshot(reader))
+    }
+}
+
+impl<T: Snapshot> Snapshot for Vec<T> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.len().write_snapshot(writer);
+        for value in self {
+            value.write_snapshot(writer);
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        let len = usize::read_snapshot(reader);
+        (0..len).map(|_| T::read_snapshot(reader)).collect()
+    }
+}
+
+impl<K: Snapshot + Eq + core::hash::Hash, V: Snapshot> Snapshot for Map<K, V> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.len().write_snapshot(writer);
+        for (key, value) in self {
+            key.write_snapshot(writer);
+            value.write_snapshot(writer);
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Self {
+        let len = usize::read_snapshot(reader);
+        (0..len)
+            .map(|_| (K::read_snapshot(reader), V::read_snapshot(reader)))
+            .collect()
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::{Entity, EntityIndexSet, index_set},
+        query::{QueryData, QueryFilter, QueryManyUniqueIter},
+        system::{Query, SystemParam},
+    },
+    platform::prelude::Vec,
+};
+
+use crate::main::StableId;
+
+/// A [`Query`] that iterates its matches ordered by their `K` component instead of the
+/// archetype/table order, which changes with spawn history and component insertion.
+///
+/// Entities without `K` are skipped, so `K` should be present (and unique) on everything the
+/// query is meant to visit. `D` must not access `K` mutably.
+#[derive(SystemParam)]
+pub struct SortedQuery<
+    'w,
+    's,
+    D: QueryData + 'static,
+    F: QueryFilter + 'static = (),
+    K: Component + Ord = StableId,
+> {
+    query: Query<'w, 's, D, F>,
+    keys: Query<'w, 's, (Entity, &'static K), F>,
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter, K: Component + Ord> SortedQuery<'w, 's, D, F, K> {
+    /// Returns the matched entities in key order.
+    #[must_use]
+    pub fn entities(&self) -> EntityIndexSet {
+        let mut keys: Vec<_> = self
+            .keys
+            .iter()
+            .filter(|(entity, _)| self.query.contains(*entity))
+            .collect();
+        keys.sort_by_key(|(_, key)| *key);
+        keys.into_iter().map(|(entity, _)| entity).collect()
+    }
+
+    pub fn iter(&self) -> QueryManyUniqueIter<'_, 's, D::ReadOnly, F, index_set::IntoIter> {
+        self.query.iter_many_unique(self.entities())
+    }
+
+    pub fn iter_mut(&mut self) -> QueryManyUniqueIter<'_, 's, D, F, index_set::IntoIter> {
+        let entities = self.entities();
+        self.query.iter_many_unique_mut(entities)
+    }
+
+    /// Like [`Query::iter_combinations_mut`] for pairs, but the pairs come in key order.
+    pub fn iter_combinations_mut(&mut self) -> SortedCombinations<'_, 'w, 's, D, F> {
+        SortedCombinations {
+            entities: self.entities().into_iter().collect(),
+            query: &mut self.query,
+            first: 0,
+            second: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn query(&self) -> &Query<'w, 's, D, F> {
+        &self.query
+    }
+
+    pub const fn query_mut(&mut self) -> &mut Query<'w, 's, D, F> {
+        &mut self.query
+    }
+}
+
+/// Every unordered pair of a [`SortedQuery`], see [`SortedQuery::iter_combinations_mut`].
+pub struct SortedCombinations<'a, 'w, 's, D: QueryData, F: QueryFilter> {
+    query: &'a mut Query<'w, 's, D, F>,
+    entities: Vec<Entity>,
+    first: usize,
+    second: usize,
+}
+
+impl<'s, D: QueryData, F: QueryFilter> SortedCombinations<'_, '_, 's, D, F> {
+    pub fn fetch_next(&mut self) -> Option<[D::Item<'_, 's>; 2]> {
+        self.second += 1;
+        if self.second >= self.entities.len() {
+            self.first += 1;
+            self.second = self.first + 1;
+        }
+        if self.second >= self.entities.len() {
+            return None;
+        }
+
+        let pair = [self.entities[self.first], self.entities[self.second]];
+        Some(self.query.get_many_mut(pair).unwrap())
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::collections::BTreeMap;
+
+use bevy::{
+    ecs::{
+        component::Mutable,
+        entity::{EntityHashMap, EntityMapper},
+        lifecycle::HookContext,
+        world::DeferredWorld,
+    },
+    prelude::*,
+};
+
+/// A deterministic identifier of a [`Subworld`](super::Subworld) entity.
+///
+/// [`Entity`] indices depend on allocation and recycling order, which differs between peers
+/// and after a snapshot restore. A `StableId` is allocated by the world itself from
+/// [`StableIds`], in the order entities get it inserted, so it is the same on every machine.
+///
+/// Insert [`StableId::default`] to allocate a new id. Inserting a specific id (e.g. when
+/// loading a snapshot) keeps it as is.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[reflect(Component, Default, Debug, PartialEq, Hash)]
+#[component(on_insert = on_insert_stable_id, on_replace = on_replace_stable_id)]
+pub struct StableId(u64);
+
+impl StableId {
+    /// Placeholder replaced with a newly allocated id on insertion.
+    pub const PENDING: Self = Self(0);
+
+    #[must_use]
+    pub const fn from_raw(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    #[must_use]
+    pub const fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    #[must_use]
+    pub const fn is_pending(self) -> bool {
+        self.0 == Self::PENDING.0
+    }
+}
+
+/// Allocates [`StableId`]s and maps them to the entities that currently hold them.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct StableIds {
+    last: u64,
+    /// Sorted, so the order doesn't depend on how the ids were inserted and despawns stay cheap.
+    entities: BTreeMap<StableId, Entity>,
+}
+
+impl StableIds {
+    #[must_use]
+    pub fn entity(&self, id: StableId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+
+    #[must_use]
+    pub fn contains(&self, id: StableId) -> bool {
+        self.entities.contains_key(&id)
+    }
+
+    /// Iterates in id order, which is the allocation order for allocated ids.
+    pub fn iter(&self) -> impl Iterator<Item = (StableId, Entity)> + '_ {
+        self.entities.iter().map(|(id, entity)| (*id, *entity))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Maps the entities known to `previous` (e.g. a copy taken before a snapshot restore,
+    /// or received from a peer) to the entities holding the same [`StableId`] in `self`.
+    ///
+    /// The result can be passed to [`Subworld::map_entities`](super::Subworld::map_entities).
+    #[must_use]
+    pub fn entity_map_from(&self, previous: &Self) -> EntityHashMap<Entity> {
+        previous
+            .iter()
+            .filter_map(|(id, old)| Some((old, self.entity(id)?)))
+            .collect()
+    }
+
+    pub(crate) const fn last(&self) -> u64 {
+        self.last
+    }
+
+    pub(crate) const fn set_last(&mut self, last: u64) {
+        self.last = last;
+    }
+
+    fn allocate(&mut self) -> StableId {
+        self.last += 1;
+        StableId(self.last)
+    }
+
+    fn insert(&mut self, id: StableId, entity: Entity) {
+        self.last = self.last.max(id.0);
+        self.entities.insert(id, entity);
+    }
+}
+
+fn on_insert_stable_id(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+    let id = *world.get::<StableId>(entity).unwrap();
+    // Outside of a Subworld there is nothing to allocate from.
+    let Some(mut ids) = world.get_resource_mut::<StableIds>() else {
+        return;
+    };
+
+    let assigned = if id.is_pending() { ids.allocate() } else { id };
+    assert!(
+        ids.entity(assigned).is_none_or(|other| other == entity),
+        "{assigned:?} is already held by another entity"
+    );
+    ids.insert(assigned, entity);
+
+    if assigned != id {
+        world.get_mut::<StableId>(entity).unwrap().0 = assigned.0;
+    }
+}
+
+fn on_replace_stable_id(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+    let id = *world.get::<StableId>(entity).unwrap();
+    if let Some(mut ids) = world.get_resource_mut::<StableIds>()
+        && ids.entity(id) == Some(entity)
+    {
+        ids.entities.remove(&id);
+    }
+}
+
+/// Remaps the [`Entity`] references held by every `C` in `world`.
+pub(crate) fn map_component_entities<C: Component<Mutability = Mutable>>(
+    world: &mut World,
+    mut mapper: &mut dyn EntityMapper,
+) {
+    let mut query = world.query::<&mut C>();
+    for mut component in query.iter_mut(world) {
+        C::map_entities(&mut *component, &mut mapper);
+    }
+}
@@ -0,0 +1,100 @@
+use core::any::TypeId;
+
+use bevy::{
+    ecs::{
+        message::Messages,
+        schedule::{IntoScheduleConfigs, Schedules, SystemSet},
+        world::FromWorld,
+    },
+    state::state::{
+        EnterSchedules, ExitSchedules, FreelyMutableState, NextState, State, StateTransition,
+        StateTransitionEvent, TransitionSchedules, setup_state_transitions_in_world,
+    },
+};
+
+use crate::main::{
+    Subworld,
+    input::UserInput,
+    schedule::{FixedUpdate, ScheduleOrder, Startup},
+    snapshot::SnapshotResourceFns,
+};
+
+impl<I: UserInput> Subworld<I> {
+    /// Adds [`State<S>`] and [`NextState<S>`], initialized with `S::from_world`.
+    ///
+    /// Transitions are applied by the [`StateTransition`] schedule, which runs right before
+    /// [`FixedUpdate`] on every tick and before [`Startup`] for the initial `OnEnter`. The
+    /// `OnExit`, `OnTransition` and `OnEnter` schedules of different states run in the order the
+    /// states were added.
+    ///
+    /// Both resources are part of [`WorldSnapshot`](super::WorldSnapshot)s. Restoring one sets
+    /// the state without running any transition schedule.
+    pub fn init_state<S: FreelyMutableState + FromWorld>(&mut self) -> &mut Self {
+        if !self.contains_resource::<State<S>>() {
+            let state = S::from_world(&mut self.world);
+            self.add_state(state);
+        }
+        self
+    }
+
+    /// Like [`Subworld::init_state`], but with the given initial state.
+    ///
+    /// If `S` was already added, this overrides the current state without running the `OnExit`
+    /// and `OnEnter` schedules of the old and new states. Set [`NextState<S>`] instead to run
+    /// them.
+    pub fn insert_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self {
+        if self.contains_resource::<State<S>>() {
+            *self.resource_mut::<State<S>>() = State::new(state);
+        } else {
+            self.add_state(state);
+        }
+        self
+    }
+
+    fn add_state<S: FreelyMutableState>(&mut self, state: S) {
+        setup_state_transitions_in_world(&mut self.world);
+        self.configure_schedules();
+        let mut order = self.resource_mut::<ScheduleOrder>();
+        if !order.contains(StateTransition) {
+            order.insert_before(FixedUpdate, StateTransition);
+            order.insert_startup_before(Startup, StateTransition);
+        }
+
+        self.insert_resource(State::new(state.clone()));
+        self.init_resource::<NextState<S>>();
+        self.register_snapshot_resource::<NextState<S>>();
+        self.snapshot_resources.insert(
+            TypeId::of::<State<S>>(),
+            SnapshotResourceFns::of_state::<S>(),
+        );
+        self.init_resource::<Messages<StateTransitionEvent<S>>>();
+        self.message_queues
+            .insert(TypeId::of::<StateTransitionEvent<S>>(), |world, _| {
+                world
+                    .resource_mut::<Messages<StateTransitionEvent<S>>>()
+                    .update();
+            });
+
+        let sets = [
+            ExitSchedules::<S>::default().intern(),
+            TransitionSchedules::<S>::default().intern(),
+            EnterSchedules::<S>::default().intern(),
+        ];
+        let previous = self.state_sets.replace(sets);
+        let mut schedules = self.resource_mut::<Schedules>();
+        let schedule = schedules.get_mut(StateTransition).unwrap();
+        S::register_state(schedule);
+        // The schedules of every state run from exclusive systems, which bevy leaves unordered.
+        if let Some(previous) = previous {
+            for (set, previous) in sets.into_iter().zip(previous) {
+                schedule.configure_sets(set.after(previous));
+            }
+        }
+        self.world.write_message(StateTransitionEvent {
+            exited: None,
+            entered: Some(state),
+            // The state didn't exist before, so there is nothing to compare with.
+            allow_same_state_transitions: true,
+        });
+    }
+}
@@ -1,2 +1,4 @@
 #[allow(clippy::disallowed_types)]
 pub type Map<K, V> = indexmap::IndexMap<K, V, core::hash::BuildHasherDefault<rustc_hash::FxHasher>>;
+#[allow(clippy::disallowed_types)]
+pub type Set<K> = indexmap::IndexSet<K, core::hash::BuildHasherDefault<rustc_hash::FxHasher>>;
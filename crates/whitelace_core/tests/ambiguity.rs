@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use whitelace_core::main::{AmbiguityPolicy, Subworld, schedule::FixedUpdate};
+
+#[derive(Resource, Default)]
+struct Counter(u32);
+
+fn first(mut counter: ResMut<Counter>) {
+    counter.0 += 1;
+}
+
+fn second(mut counter: ResMut<Counter>) {
+    counter.0 *= 2;
+}
+
+fn unordered_writers() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world.init_resource::<Counter>();
+    world.add_systems(FixedUpdate, (first, second));
+    world
+}
+
+#[test]
+fn check_lists_unordered_writers() {
+    let mut world = unordered_writers();
+    let ambiguities = world.check_ambiguities();
+    assert_eq!(ambiguities.len(), 1);
+    assert_eq!(ambiguities[0].conflicts.len(), 1);
+    assert!(ambiguities[0].conflicts[0].ends_with("Counter"));
+}
+
+#[test]
+fn ordered_writers_are_not_ambiguous() {
+    let mut world = Subworld::<()>::default();
+    world.init_resource::<Counter>();
+    world.add_systems(FixedUpdate, (first, second).chain());
+    assert!(world.check_ambiguities().is_empty());
+    world.tick(Vec::new());
+    assert_eq!(world.resource::<Counter>().0, 2);
+}
+
+#[test]
+fn unordered_writers_warn_by_default() {
+    assert_eq!(AmbiguityPolicy::default(), AmbiguityPolicy::Warn);
+    let mut world = unordered_writers();
+    world.tick(Vec::new());
+    assert_eq!(world.check_ambiguities().len(), 1);
+}
+
+#[test]
+#[should_panic = "conflicting access and no explicit ordering"]
+fn panic_policy_fails_the_build() {
+    let mut world = unordered_writers();
+    world.set_ambiguity_policy(AmbiguityPolicy::Panic);
+    world.finish();
+}
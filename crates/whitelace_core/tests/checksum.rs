@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use whitelace_core::{
+    main::{Checksum, StableId, Subworld},
+    math::{FVec3, Fx},
+};
+
+#[derive(Component, Checksum, Debug, Clone)]
+struct Body {
+    position: FVec3,
+    target: Option<Entity>,
+    #[checksum(skip)]
+    cache: u32,
+}
+
+#[derive(Component)]
+struct Unregistered;
+
+fn world() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world.register_checksum::<Body>();
+    world
+}
+
+fn populate(world: &mut Subworld) {
+    let first = world
+        .spawn((
+            StableId::default(),
+            Body {
+                position: FVec3::new(Fx::from_bits(1), Fx::ZERO, Fx::ZERO),
+                target: None,
+                cache: 0,
+            },
+        ))
+        .id();
+    world.spawn((
+        StableId::default(),
+        Body {
+            position: FVec3::new(Fx::from_bits(2), Fx::ZERO, Fx::ZERO),
+            target: Some(first),
+            cache: 0,
+        },
+    ));
+}
+
+#[test]
+fn same_state_same_checksum() {
+    let mut world_a = world();
+    populate(&mut world_a);
+
+    // Different entity indices, and state the checksum doesn't cover.
+    let mut world_b = world();
+    for _ in 0..5 {
+        world_b.spawn_empty();
+    }
+    world_b.spawn(Unregistered);
+    populate(&mut world_b);
+    for mut body in world_b.query::<&mut Body>().iter_mut(&mut world_b) {
+        body.cache = 42;
+    }
+
+    assert_eq!(world_a.checksum(), world_b.checksum());
+}
+
+#[test]
+fn checksum_is_stable() {
+    let mut world = world();
+    populate(&mut world);
+    // FNV-1a over little endian bytes, the same on every platform and build.
+    assert_eq!(world.checksum(), world.checksum());
+    let mut other = self::world();
+    populate(&mut other);
+    assert_eq!(world.checksum(), other.checksum());
+}
+
+#[test]
+fn changes_change_the_checksum() {
+    let mut world = world();
+    populate(&mut world);
+    let before = world.checksum();
+
+    for mut body in world.query::<&mut Body>().iter_mut(&mut world) {
+        body.position.x += Fx::from_bits(1);
+    }
+    assert_ne!(world.checksum(), before);
+}
+
+#[test]
+fn ticks_change_the_checksum() {
+    let mut world = world();
+    populate(&mut world);
+    let before = world.checksum();
+    world.tick(Vec::new());
+    assert_ne!(world.checksum(), before);
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use whitelace_core::main::{
+    ScheduledCommands, Subworld,
+    schedule::{FixedUpdate, SimTick},
+};
+
+#[derive(Resource, Default)]
+struct Log(Vec<u64>);
+
+fn log(world: &mut World) {
+    let tick = world.resource::<SimTick>().get();
+    world.resource_mut::<Log>().0.push(tick);
+}
+
+fn world() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world.init_resource::<Log>();
+    world
+}
+
+#[test]
+fn restored_commands_run_again() {
+    let mut world = world();
+    world
+        .resource_mut::<ScheduledCommands>()
+        .schedule(SimTick::new(1), log);
+    world.tick(Vec::new());
+    let snapshot = world.snapshot();
+
+    world.tick(Vec::new());
+    assert_eq!(world.resource::<Log>().0, [1]);
+    assert!(world.resource::<ScheduledCommands>().is_empty());
+
+    world.restore(&snapshot);
+    assert_eq!(world.resource::<ScheduledCommands>().len(), 1);
+    world.tick(Vec::new());
+    assert_eq!(world.resource::<Log>().0, [1, 1]);
+}
+
+#[test]
+fn schedule_in_is_relative_to_the_sim_tick() {
+    let mut world = world();
+    world.add_systems(FixedUpdate, |mut commands: ResMut<ScheduledCommands>| {
+        if commands.tick() == SimTick::new(0) {
+            commands.schedule_in(1, log);
+        }
+    });
+    world.tick(Vec::new());
+
+    // Between ticks the sim tick is already the next one.
+    world
+        .resource_mut::<ScheduledCommands>()
+        .schedule_in(0, log);
+    world
+        .resource_mut::<ScheduledCommands>()
+        .schedule_in(1, log);
+    world.tick(Vec::new());
+    world.tick(Vec::new());
+    assert_eq!(world.resource::<Log>().0, [1, 1, 2]);
+}
+
+#[test]
+fn cancelled_commands_dont_run() {
+    let mut world = world();
+    let id = world
+        .resource_mut::<ScheduledCommands>()
+        .schedule(SimTick::new(0), log);
+    assert!(world.resource_mut::<ScheduledCommands>().cancel(id));
+    assert!(!world.resource_mut::<ScheduledCommands>().cancel(id));
+    world.tick(Vec::new());
+    assert!(world.resource::<Log>().0.is_empty());
+}
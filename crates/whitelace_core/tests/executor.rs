@@ -0,0 +1,44 @@
+#![cfg(feature = "multi_threaded")]
+
+use bevy::prelude::*;
+use whitelace_core::main::{
+    Subworld,
+    schedule::{FixedUpdate, SubworldExecutor},
+};
+
+#[derive(Component, Debug, PartialEq, Eq, Clone, Copy)]
+struct Spawned(u32);
+
+fn spawner(id: u32) -> impl FnMut(Commands) {
+    move |mut commands: Commands| {
+        for _ in 0..16 {
+            commands.spawn(Spawned(id));
+        }
+    }
+}
+
+fn run(executor: SubworldExecutor) -> Vec<(Entity, Spawned)> {
+    let mut world = Subworld::<()>::default();
+    world.set_executor(executor);
+    for id in 0..8 {
+        world.add_systems(FixedUpdate, spawner(id));
+    }
+    for _ in 0..4 {
+        world.tick(Vec::new());
+    }
+    let mut spawned: Vec<_> = world
+        .query::<(Entity, &Spawned)>()
+        .iter(&world)
+        .map(|(entity, spawned)| (entity, *spawned))
+        .collect();
+    spawned.sort_by_key(|(entity, _)| *entity);
+    spawned
+}
+
+#[test]
+fn parallel_spawns_match_single_threaded() {
+    let expected = run(SubworldExecutor::SingleThreaded);
+    for _ in 0..8 {
+        assert_eq!(run(SubworldExecutor::MultiThreaded), expected);
+    }
+}
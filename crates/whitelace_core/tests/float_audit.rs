@@ -0,0 +1,88 @@
+use bevy::{prelude::*, reflect::GetTypeRegistration};
+use whitelace_core::main::{FloatPolicy, Subworld};
+
+#[derive(Component, Reflect, Default)]
+struct Speed {
+    value: f32,
+}
+
+#[derive(Component, Default)]
+struct Unregistered {
+    _value: f32,
+}
+
+#[derive(Component, Reflect, Default)]
+struct Outer {
+    inner: Inner,
+}
+
+#[derive(Reflect, Default)]
+struct Inner;
+
+#[derive(Component, Default)]
+struct Marker;
+
+fn fields(world: &Subworld) -> Vec<(String, String, Option<&'static str>)> {
+    world
+        .audit_floats()
+        .into_iter()
+        .map(|field| (field.owner, field.field, field.ty))
+        .collect()
+}
+
+#[test]
+fn default_world_is_clean() {
+    let mut world = Subworld::<()>::default();
+    world.tick(Vec::new());
+    assert_eq!(world.audit_floats(), []);
+}
+
+#[test]
+fn finds_reflected_floats() {
+    let mut world = Subworld::<()>::default();
+    world.register_type::<Speed>();
+    world.spawn(Speed::default());
+    let found = fields(&world);
+    assert_eq!(found.len(), 1);
+    assert!(found[0].0.ends_with("Speed"));
+    assert_eq!(found[0].1, ".value");
+    assert_eq!(found[0].2, Some("f32"));
+}
+
+#[test]
+fn reports_unregistered_types() {
+    let mut world = Subworld::<()>::default();
+    world.spawn((Unregistered::default(), Marker));
+    let found = fields(&world);
+    assert_eq!(found.len(), 1);
+    assert!(found[0].0.ends_with("Unregistered"));
+    assert_eq!(found[0].2, None);
+
+    world.skip_float_audit::<Unregistered>();
+    assert_eq!(world.audit_floats(), []);
+}
+
+#[test]
+fn reports_unregistered_fields() {
+    let mut world = Subworld::<()>::default();
+    world.spawn(Outer::default());
+    // Registering `Outer` by hand skips its dependencies.
+    world
+        .resource::<AppTypeRegistry>()
+        .write()
+        .add_registration(Outer::get_type_registration());
+    let found = fields(&world);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].1, ".inner");
+    assert_eq!(found[0].2, None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "not registered for reflection"]
+fn panic_policy_covers_unregistered_types() {
+    let mut world = Subworld::<()>::default();
+    world.set_float_policy(FloatPolicy::Panic);
+    world.spawn(Unregistered::default());
+    world.tick(Vec::new());
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use whitelace_core::main::{
+    DMessageReader, DMessageWriter, DMessages, Subworld,
+    schedule::{FixedUpdate, PostFixedUpdate, SimTick},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Ping(u32);
+
+#[derive(Resource, Default)]
+struct Seen(Vec<u32>);
+
+fn write_tick(mut writer: DMessageWriter<Ping>, tick: Res<SimTick>) {
+    writer.write(Ping(u32::try_from(tick.get()).unwrap()));
+}
+
+fn read(reader: DMessageReader<Ping>, mut seen: ResMut<Seen>) {
+    seen.0.extend(reader.read().map(|ping| ping.0));
+}
+
+fn world() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world.add_message::<Ping>().init_resource::<Seen>();
+    world
+}
+
+#[test]
+fn readers_see_messages_of_the_tick() {
+    let mut world = world();
+    world.add_systems(FixedUpdate, (write_tick, read).chain());
+    world.tick(Vec::new());
+    world.tick(Vec::new());
+    assert_eq!(world.resource::<Seen>().0, [0, 1]);
+}
+
+#[test]
+fn messages_only_live_for_their_tick() {
+    let mut world = world();
+    world.add_systems(FixedUpdate, read);
+    world.add_systems(PostFixedUpdate, write_tick);
+    world.tick(Vec::new());
+    world.tick(Vec::new());
+    assert!(world.resource::<Seen>().0.is_empty());
+
+    // Still there between ticks.
+    let messages = world.resource::<DMessages<Ping>>();
+    assert_eq!(messages.tick(), SimTick::new(1));
+    assert_eq!(messages.iter().cloned().collect::<Vec<_>>(), [Ping(1)]);
+}
+
+#[test]
+fn forks_copy_messages() {
+    let mut world = world();
+    world.add_systems(PostFixedUpdate, write_tick);
+    world.tick(Vec::new());
+    let fork = world.fork();
+    let messages = fork.resource::<DMessages<Ping>>();
+    assert_eq!(messages.iter().cloned().collect::<Vec<_>>(), [Ping(0)]);
+}
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use whitelace_core::main::{DPlugin, DPluginGroup, DPluginGroupBuilder, DPluginsState, Subworld};
+
+#[derive(Resource, Default)]
+struct Log(Vec<String>);
+
+fn log(world: &mut Subworld, entry: String) {
+    world.get_resource_or_init::<Log>().0.push(entry);
+}
+
+/// Logs every step of its lifecycle.
+#[derive(Default)]
+struct Logged<const ID: char>;
+
+impl<const ID: char> DPlugin<()> for Logged<ID> {
+    fn build(&self, world: &mut Subworld) {
+        log(world, format!("build {ID}"));
+    }
+
+    fn finish(&self, world: &mut Subworld) {
+        log(world, format!("finish {ID}"));
+    }
+
+    fn cleanup(&self, world: &mut Subworld) {
+        log(world, format!("cleanup {ID}"));
+    }
+}
+
+/// Requires `Logged<'c'>` while it is built.
+struct Parent;
+
+impl DPlugin<()> for Parent {
+    fn build(&self, world: &mut Subworld) {
+        log(world, "build parent".to_string());
+        world.require_plugin::<Logged<'c'>>();
+    }
+
+    fn finish(&self, world: &mut Subworld) {
+        log(world, "finish parent".to_string());
+    }
+}
+
+struct Repeated;
+
+impl DPlugin<()> for Repeated {
+    fn build(&self, world: &mut Subworld) {
+        log(world, "build repeated".to_string());
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+struct Group;
+
+impl DPluginGroup<()> for Group {
+    fn build(self) -> DPluginGroupBuilder<()> {
+        DPluginGroupBuilder::start::<Self>()
+            .add(Logged::<'a'>)
+            .add(Logged::<'b'>)
+            .add(Logged::<'c'>)
+    }
+}
+
+fn entries(world: &Subworld) -> Vec<&str> {
+    world
+        .resource::<Log>()
+        .0
+        .iter()
+        .map(String::as_str)
+        .collect()
+}
+
+#[test]
+#[should_panic = "plugin was already added to the world"]
+fn unique_plugins_are_added_once() {
+    let mut world = Subworld::<()>::default();
+    world.add_plugin(Logged::<'a'>).add_plugin(Logged::<'a'>);
+}
+
+#[test]
+fn non_unique_plugins_are_added_again() {
+    let mut world = Subworld::<()>::default();
+    world.add_plugin(Repeated).add_plugin(Repeated);
+    assert_eq!(world.get_added_plugins::<Repeated>().len(), 2);
+    assert_eq!(entries(&world), ["build repeated", "build repeated"]);
+}
+
+#[test]
+fn required_plugins_are_built_once() {
+    let mut world = Subworld::<()>::default();
+    world
+        .require_plugin::<Logged<'a'>>()
+        .require_plugin::<Logged<'a'>>();
+    world.add_plugin(Parent);
+    world.require_plugin::<Logged<'c'>>();
+
+    assert!(world.is_plugin_added::<Logged<'c'>>());
+    assert_eq!(entries(&world), ["build a", "build parent", "build c"]);
+}
+
+#[test]
+fn groups_skip_disabled_and_added_plugins() {
+    let mut world = Subworld::<()>::default();
+    world.add_plugin(Logged::<'c'>);
+    world.add_plugins(Group.build().disable::<Logged<'b'>>());
+
+    assert!(!world.is_plugin_added::<Logged<'b'>>());
+    assert_eq!(world.get_added_plugins::<Logged<'c'>>().len(), 1);
+    assert_eq!(entries(&world), ["build c", "build a"]);
+}
+
+#[test]
+fn plugins_finish_then_clean_up_in_order() {
+    let mut world = Subworld::<()>::default();
+    world
+        .add_plugin(Logged::<'a'>)
+        .add_plugin(Parent)
+        .add_plugin(Logged::<'b'>);
+    assert_eq!(world.plugins_state(), DPluginsState::Adding);
+
+    world.finish();
+    assert_eq!(world.plugins_state(), DPluginsState::Finished);
+    world.tick(Vec::new());
+    assert_eq!(world.plugins_state(), DPluginsState::Cleaned);
+    world.tick(Vec::new());
+
+    assert_eq!(
+        entries(&world),
+        [
+            "build a",
+            "build parent",
+            "build c",
+            "build b",
+            "finish a",
+            "finish parent",
+            "finish c",
+            "finish b",
+            "cleanup a",
+            "cleanup c",
+            "cleanup b",
+        ]
+    );
+}
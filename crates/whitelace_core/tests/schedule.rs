@@ -0,0 +1,147 @@
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+use whitelace_core::main::{
+    Subworld,
+    schedule::{
+        FixedFirst, FixedUpdate, Physics, PostFixedUpdate, PreFixedUpdate, ScheduleOrder, Startup,
+    },
+};
+
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+struct LateStartup;
+
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+struct EarlyStartup;
+
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+struct Input;
+
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+struct Audio;
+
+#[derive(Resource, Default)]
+struct Log(Vec<&'static str>);
+
+#[test]
+fn startup_schedules_run_once_in_order() {
+    let mut world = Subworld::<()>::default();
+    world.init_resource::<Log>();
+    world
+        .resource_mut::<ScheduleOrder>()
+        .push_startup(LateStartup);
+    world.add_systems(Startup, |mut log: ResMut<Log>| log.0.push("startup"));
+    world.add_systems(LateStartup, |mut log: ResMut<Log>| log.0.push("late"));
+    world.add_systems(FixedUpdate, |mut log: ResMut<Log>| log.0.push("tick"));
+
+    world.tick(Vec::new());
+    world.tick(Vec::new());
+
+    assert_eq!(
+        world.resource::<Log>().0,
+        ["startup", "late", "tick", "tick"]
+    );
+}
+
+#[test]
+fn startup_order_can_be_edited() {
+    let mut order = ScheduleOrder::default();
+    assert!(order.contains_startup(Startup));
+    assert!(!order.contains(Startup));
+
+    order.push_startup(LateStartup);
+    assert!(order.contains_startup(LateStartup));
+    assert!(order.remove_startup(LateStartup));
+    assert!(!order.remove_startup(LateStartup));
+    assert!(!order.contains_startup(LateStartup));
+}
+
+#[test]
+fn schedules_are_inserted_next_to_their_anchor() {
+    let mut order = ScheduleOrder::default();
+    order.insert_before(Physics, Input);
+    order.insert_after(FixedUpdate, Audio);
+    assert_eq!(
+        order.labels,
+        [
+            FixedFirst.intern(),
+            Input.intern(),
+            Physics.intern(),
+            PreFixedUpdate.intern(),
+            FixedUpdate.intern(),
+            Audio.intern(),
+            PostFixedUpdate.intern(),
+        ]
+    );
+
+    order.insert_startup_before(Startup, EarlyStartup);
+    order.insert_startup_after(Startup, LateStartup);
+    assert_eq!(
+        order.startup_labels,
+        [
+            EarlyStartup.intern(),
+            Startup.intern(),
+            LateStartup.intern()
+        ]
+    );
+}
+
+#[test]
+#[should_panic = "Expected Audio to be in the ScheduleOrder"]
+fn inserting_after_a_missing_schedule_panics() {
+    ScheduleOrder::default().insert_after(Audio, Input);
+}
+
+#[test]
+#[should_panic = "Expected FixedUpdate to be in the ScheduleOrder"]
+fn inserting_before_a_missing_startup_schedule_panics() {
+    ScheduleOrder::default().insert_startup_before(FixedUpdate, EarlyStartup);
+}
+
+#[test]
+fn removed_schedules_can_be_added_again() {
+    let mut world = Subworld::<()>::default();
+    world.init_resource::<Log>();
+    world.add_systems(Audio, |mut log: ResMut<Log>| log.0.push("audio"));
+    world.add_systems(FixedUpdate, |mut log: ResMut<Log>| log.0.push("tick"));
+
+    world
+        .resource_mut::<ScheduleOrder>()
+        .insert_before(FixedUpdate, Audio);
+    world.tick(Vec::new());
+
+    let mut order = world.resource_mut::<ScheduleOrder>();
+    assert!(order.remove(Audio));
+    assert!(!order.remove(Audio));
+    assert!(!order.contains(Audio));
+    world.tick(Vec::new());
+
+    world
+        .resource_mut::<ScheduleOrder>()
+        .insert_after(FixedUpdate, Audio);
+    world.tick(Vec::new());
+
+    assert_eq!(
+        world.resource::<Log>().0,
+        ["audio", "tick", "tick", "tick", "audio"]
+    );
+}
+
+#[test]
+fn startup_schedules_only_run_on_the_first_tick() {
+    let mut world = Subworld::<()>::default();
+    world.init_resource::<Log>();
+    world.add_systems(EarlyStartup, |mut log: ResMut<Log>| log.0.push("early"));
+    world.add_systems(LateStartup, |mut log: ResMut<Log>| log.0.push("late"));
+    world
+        .resource_mut::<ScheduleOrder>()
+        .insert_startup_before(Startup, EarlyStartup);
+    world.tick(Vec::new());
+
+    // Added after the first tick, so never run.
+    world
+        .resource_mut::<ScheduleOrder>()
+        .insert_startup_after(Startup, LateStartup);
+    world.tick(Vec::new());
+    world.tick(Vec::new());
+
+    assert_eq!(world.resource::<Log>().0, ["early"]);
+}
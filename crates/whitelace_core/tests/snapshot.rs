@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use whitelace_core::{
+    main::{
+        Checksum, DMessageWriter, DMessages, Snapshot, StableId, Subworld,
+        schedule::{FixedUpdate, SimTick},
+    },
+    math::{FVec3, Fx},
+};
+
+#[derive(Component, Snapshot, Checksum, Debug, Clone, PartialEq, Eq)]
+struct Body {
+    position: FVec3,
+    mass: Fx,
+    target: Option<Entity>,
+    #[snapshot(skip)]
+    #[checksum(skip)]
+    cache: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Hit(u32);
+
+fn body(x: i64, target: Option<Entity>) -> Body {
+    Body {
+        position: FVec3::new(Fx::from_bits(x), Fx::from_num(1), Fx::ZERO),
+        mass: Fx::from_bits(x * 3 + 1),
+        target,
+        cache: 7,
+    }
+}
+
+fn world() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world
+        .register_snapshot::<Body>()
+        .register_checksum::<Body>();
+    world.add_message::<Hit>();
+    world.add_systems(
+        FixedUpdate,
+        |mut bodies: Query<&mut Body>, mut hits: DMessageWriter<Hit>| {
+            for mut body in &mut bodies {
+                body.position.x += Fx::from_bits(5);
+                hits.write(Hit(u32::try_from(body.mass.to_bits()).unwrap()));
+            }
+        },
+    );
+    world
+}
+
+#[test]
+fn restore_round_trips() {
+    let mut world = world();
+    let first = world.spawn((StableId::default(), body(10, None))).id();
+    world.spawn((StableId::default(), body(20, Some(first))));
+    world.tick(Vec::new());
+    let snapshot = world.snapshot();
+    let checksum = world.checksum();
+
+    world.tick(Vec::new());
+    world.spawn((StableId::default(), body(30, None)));
+    world.despawn(first);
+    world.tick(Vec::new());
+    assert_ne!(world.checksum(), checksum);
+
+    world.restore(&snapshot);
+    assert_eq!(*world.resource::<SimTick>(), SimTick::new(1));
+    assert_eq!(world.checksum(), checksum);
+    assert_eq!(world.resource::<DMessages<Hit>>().len(), 2);
+
+    // The despawned entity is spawned again, and references to it are remapped.
+    let mut bodies: Vec<_> = world
+        .query::<(Entity, &StableId, &Body)>()
+        .iter(&world)
+        .map(|(entity, id, body)| (id.to_raw(), entity, body.clone()))
+        .collect();
+    bodies.sort_by_key(|(id, _, _)| *id);
+    assert_eq!(bodies.len(), 2);
+    assert_eq!(bodies[1].2.target, Some(bodies[0].1));
+    assert_eq!(bodies[0].2.position.x, Fx::from_bits(15));
+    assert_eq!(bodies[0].2.cache, 0);
+}
+
+#[test]
+fn resimulation_matches() {
+    let mut world = world();
+    world.spawn((StableId::default(), body(10, None)));
+    let snapshot = world.snapshot();
+    let mut checksums = Vec::new();
+    for _ in 0..4 {
+        world.tick(Vec::new());
+        checksums.push(world.checksum());
+    }
+
+    world.restore(&snapshot);
+    for checksum in checksums {
+        world.tick(Vec::new());
+        assert_eq!(world.checksum(), checksum);
+    }
+}
+
+#[test]
+#[should_panic = "isn't registered"]
+fn restoring_unregistered_components_panics() {
+    let mut world = world();
+    world.spawn((StableId::default(), body(10, None)));
+    let snapshot = world.snapshot();
+    Subworld::<()>::default().restore(&snapshot);
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use whitelace_core::main::{SortedQuery, StableId, Subworld};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct Value(u32);
+
+/// Splits the entities across archetypes, so the query order isn't the spawn order either.
+#[derive(Component)]
+struct Marker;
+
+#[derive(Component, PartialEq, Eq, PartialOrd, Ord)]
+struct Priority(i32);
+
+/// Entities spawned out of id order, each with its id as value.
+fn world() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    for raw in [3, 1, 4, 2] {
+        let mut entity = world.spawn((StableId::from_raw(raw), Value(u32::try_from(raw).unwrap())));
+        if raw % 2 == 0 {
+            entity.insert(Marker);
+        }
+    }
+    world
+}
+
+#[test]
+fn iteration_follows_stable_ids() {
+    let mut world = world();
+    let values = world
+        .run_system_once(|query: SortedQuery<&Value>| {
+            query.iter().map(|value| value.0).collect::<Vec<_>>()
+        })
+        .unwrap();
+    assert_eq!(values, [1, 2, 3, 4]);
+
+    let marked = world
+        .run_system_once(|query: SortedQuery<&Value, With<Marker>>| {
+            query.iter().map(|value| value.0).collect::<Vec<_>>()
+        })
+        .unwrap();
+    assert_eq!(marked, [2, 4]);
+}
+
+#[test]
+fn mutable_iteration_follows_stable_ids() {
+    let mut world = world();
+    let order = world
+        .run_system_once(|mut query: SortedQuery<&mut Value>| {
+            let mut order = Vec::new();
+            for (index, mut value) in (0..).zip(query.iter_mut()) {
+                order.push(value.0);
+                value.0 = index;
+            }
+            order
+        })
+        .unwrap();
+    assert_eq!(order, [1, 2, 3, 4]);
+    let id_of_first = world
+        .query::<(&StableId, &Value)>()
+        .iter(&world)
+        .find(|(_, value)| value.0 == 0)
+        .map(|(id, _)| id.to_raw());
+    assert_eq!(id_of_first, Some(1));
+}
+
+#[test]
+fn combinations_come_in_key_order() {
+    let mut world = world();
+    let pairs = world
+        .run_system_once(|mut query: SortedQuery<&Value>| {
+            let mut pairs = Vec::new();
+            let mut combinations = query.iter_combinations_mut();
+            while let Some([first, second]) = combinations.fetch_next() {
+                pairs.push((first.0, second.0));
+            }
+            pairs
+        })
+        .unwrap();
+    assert_eq!(pairs, [(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)]);
+}
+
+#[test]
+fn entities_without_a_key_are_skipped() {
+    let mut world = world();
+    world.spawn(Value(0));
+    let (sorted, all) = world
+        .run_system_once(|query: SortedQuery<&Value>| {
+            (query.iter().count(), query.query().iter().count())
+        })
+        .unwrap();
+    assert_eq!((sorted, all), (4, 5));
+
+    let pairs = world
+        .run_system_once(|mut query: SortedQuery<&Value>| {
+            let mut combinations = query.iter_combinations_mut();
+            let mut count = 0;
+            while combinations.fetch_next().is_some() {
+                count += 1;
+            }
+            count
+        })
+        .unwrap();
+    assert_eq!(pairs, 6);
+}
+
+#[test]
+fn custom_keys_order_the_iteration() {
+    let mut world = Subworld::<()>::default();
+    for (value, priority) in [(1, 5), (2, -3), (3, 0)] {
+        world.spawn((Value(value), Priority(priority)));
+    }
+    world.spawn(Value(4));
+    let values = world
+        .run_system_once(|query: SortedQuery<&Value, (), Priority>| {
+            query.iter().map(|value| value.0).collect::<Vec<_>>()
+        })
+        .unwrap();
+    assert_eq!(values, [2, 3, 1]);
+}
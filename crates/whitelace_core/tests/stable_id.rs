@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use whitelace_core::main::{StableId, StableIds, Subworld};
+
+#[test]
+fn ids_are_allocated_in_insertion_order() {
+    let mut world = Subworld::<()>::default();
+    let entities: Vec<Entity> = (0..4)
+        .map(|_| world.spawn(StableId::default()).id())
+        .collect();
+    let ids: Vec<u64> = entities
+        .iter()
+        .map(|&entity| world.get::<StableId>(entity).unwrap().to_raw())
+        .collect();
+    assert_eq!(ids, [1, 2, 3, 4]);
+    assert_eq!(
+        world.resource::<StableIds>().entity(StableId::from_raw(3)),
+        Some(entities[2])
+    );
+}
+
+#[test]
+fn iteration_is_in_id_order_for_explicit_ids() {
+    let mut world = Subworld::<()>::default();
+    for raw in [7, 2, 5] {
+        world.spawn(StableId::from_raw(raw));
+    }
+    let allocated = world.spawn(StableId::default()).id();
+    assert_eq!(world.get::<StableId>(allocated).unwrap().to_raw(), 8);
+
+    let ids: Vec<u64> = world
+        .resource::<StableIds>()
+        .iter()
+        .map(|(id, _)| id.to_raw())
+        .collect();
+    assert_eq!(ids, [2, 5, 7, 8]);
+}
+
+#[test]
+fn despawns_release_ids() {
+    let mut world = Subworld::<()>::default();
+    let entities: Vec<Entity> = (0..1000)
+        .map(|_| world.spawn(StableId::default()).id())
+        .collect();
+    for &entity in entities.iter().step_by(2) {
+        world.despawn(entity);
+    }
+    let ids = world.resource::<StableIds>();
+    assert_eq!(ids.len(), 500);
+    assert!(ids.iter().all(|(id, _)| id.to_raw() % 2 == 0));
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use whitelace_core::main::Subworld;
+
+#[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+enum Phase {
+    #[default]
+    Lobby,
+    Playing,
+}
+
+#[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+enum Weather {
+    #[default]
+    Clear,
+    Rain,
+}
+
+#[derive(Resource, Default)]
+struct Log(Vec<&'static str>);
+
+fn log(entry: &'static str) -> impl FnMut(ResMut<Log>) {
+    move |mut log: ResMut<Log>| log.0.push(entry)
+}
+
+fn world() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world.init_resource::<Log>();
+    world.init_state::<Phase>().init_state::<Weather>();
+    world.add_systems(OnEnter(Phase::Lobby), log("lobby"));
+    world.add_systems(OnEnter(Phase::Playing), log("playing"));
+    world.add_systems(OnEnter(Weather::Clear), log("clear"));
+    world.add_systems(OnEnter(Weather::Rain), log("rain"));
+    world
+}
+
+#[test]
+fn transitions_of_several_states_are_ordered() {
+    let mut world = world();
+    assert!(world.check_ambiguities().is_empty());
+    world.finish();
+    world.tick(Vec::new());
+    world
+        .resource_mut::<NextState<Weather>>()
+        .set(Weather::Rain);
+    world.resource_mut::<NextState<Phase>>().set(Phase::Playing);
+    world.tick(Vec::new());
+    assert_eq!(
+        world.resource::<Log>().0,
+        ["lobby", "clear", "playing", "rain"]
+    );
+}
+
+#[test]
+fn states_are_restored() {
+    let mut world = world();
+    world.tick(Vec::new());
+    let snapshot = world.snapshot();
+
+    world.resource_mut::<NextState<Phase>>().set(Phase::Playing);
+    world.tick(Vec::new());
+    world
+        .resource_mut::<NextState<Weather>>()
+        .set(Weather::Rain);
+    assert_eq!(*world.resource::<State<Phase>>().get(), Phase::Playing);
+
+    world.restore(&snapshot);
+    assert_eq!(*world.resource::<State<Phase>>().get(), Phase::Lobby);
+    assert!(matches!(
+        *world.resource::<NextState<Weather>>(),
+        NextState::Unchanged
+    ));
+
+    // Restoring doesn't run transitions, so the next tick enters `Playing` again.
+    world.resource_mut::<NextState<Phase>>().set(Phase::Playing);
+    world.tick(Vec::new());
+    assert_eq!(
+        world.resource::<Log>().0,
+        ["lobby", "clear", "playing", "playing"]
+    );
+}
+
+#[test]
+fn inserting_a_state_skips_transitions() {
+    let mut world = world();
+    world.tick(Vec::new());
+    world.insert_state(Phase::Playing);
+    world.tick(Vec::new());
+    assert_eq!(*world.resource::<State<Phase>>().get(), Phase::Playing);
+    assert_eq!(world.resource::<Log>().0, ["lobby", "clear"]);
+}
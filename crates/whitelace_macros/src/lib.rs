@@ -0,0 +1,277 @@
+//! Derives for `whitelace_core` and `whitelace_sync`, re-exported by those crates.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Error, Fields, Generics, Ident, Path, Result, parse_macro_input, parse_quote,
+};
+
+/// Implements `Snapshot` with a field-wise exact encoding. The derive doesn't register the
+/// component: register it with `Subworld::register_snapshot`, usually in the `DPlugin` that adds
+/// it.
+///
+/// Fields marked `#[snapshot(skip)]` are not saved and are restored with their `Default`.
+#[proc_macro_derive(Snapshot, attributes(snapshot))]
+pub fn derive_snapshot(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_snapshot(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `Checksum` by hashing every field in declaration order. Like `Snapshot`, the
+/// component still has to be registered, with `Subworld::register_checksum`.
+///
+/// Fields marked `#[checksum(skip)]` are not hashed.
+#[proc_macro_derive(Checksum, attributes(checksum))]
+pub fn derive_checksum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_checksum(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `WorldLabel` for a type that is `Clone + Eq + Hash + Debug`.
+#[proc_macro_derive(WorldLabel)]
+pub fn derive_world_label(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let sync: Path = parse_quote!(::whitelace_sync);
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.push(parse_quote! {
+        Self: 'static + ::core::marker::Send + ::core::marker::Sync + ::core::clone::Clone
+            + ::core::cmp::Eq + ::core::fmt::Debug + ::core::hash::Hash
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #sync::WorldLabel for #name #ty_generics #where_clause {
+            fn dyn_clone(&self) -> #sync::__macro_exports::Box<dyn #sync::WorldLabel> {
+                #sync::__macro_exports::Box::new(::core::clone::Clone::clone(self))
+            }
+        }
+    }
+    .into()
+}
+
+fn core_path() -> Path {
+    parse_quote!(::whitelace_core::main)
+}
+
+fn expand_snapshot(input: &DeriveInput) -> Result<TokenStream2> {
+    let main = core_path();
+    let name = &input.ident;
+    let generics = add_bound(&input.generics, &parse_quote!(#main::Snapshot));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (write, read) = match &input.data {
+        Data::Struct(data) => {
+            let bindings = bind_fields(&data.fields);
+            let pattern = destructure(&quote!(Self), &data.fields, &bindings);
+            let write = write_fields(&data.fields, &bindings)?;
+            let read = read_fields(&quote!(Self), &data.fields)?;
+            (
+                quote! {
+                    let #pattern = self;
+                    #write
+                },
+                read,
+            )
+        }
+        Data::Enum(data) => {
+            let mut write_arms = Vec::new();
+            let mut read_arms = Vec::new();
+            for (index, variant) in data.variants.iter().enumerate() {
+                let index = u32::try_from(index)
+                    .map_err(|_| Error::new_spanned(variant, "too many variants"))?;
+                let ident = &variant.ident;
+                let path = quote!(Self::#ident);
+                let bindings = bind_fields(&variant.fields);
+                let pattern = destructure(&path, &variant.fields, &bindings);
+                let write = write_fields(&variant.fields, &bindings)?;
+                let read = read_fields(&path, &variant.fields)?;
+                write_arms.push(quote! {
+                    #pattern => {
+                        #main::Snapshot::write_snapshot(&#index, writer);
+                        #write
+                    }
+                });
+                read_arms.push(quote!(#index => #read));
+            }
+            let name = name.to_string();
+            (
+                quote! {
+                    match self {
+                        #(#write_arms)*
+                    }
+                },
+                quote! {
+                    match <u32 as #main::Snapshot>::read_snapshot(reader) {
+                        #(#read_arms,)*
+                        variant => panic!("invalid snapshot variant {variant} of `{}`", #name),
+                    }
+                },
+            )
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "`Snapshot` can't be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #main::Snapshot for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn write_snapshot(&self, writer: &mut #main::SnapshotWriter) {
+                #write
+            }
+
+            #[allow(unused_variables)]
+            fn read_snapshot(reader: &mut #main::SnapshotReader) -> Self {
+                #read
+            }
+        }
+    })
+}
+
+fn expand_checksum(input: &DeriveInput) -> Result<TokenStream2> {
+    let main = core_path();
+    let name = &input.ident;
+    let generics = add_bound(&input.generics, &parse_quote!(#main::Checksum));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let bindings = bind_fields(&data.fields);
+            let pattern = destructure(&quote!(Self), &data.fields, &bindings);
+            let hash = hash_fields(&data.fields, &bindings)?;
+            quote! {
+                let #pattern = self;
+                #hash
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for (index, variant) in data.variants.iter().enumerate() {
+                let index = u32::try_from(index)
+                    .map_err(|_| Error::new_spanned(variant, "too many variants"))?;
+                let ident = &variant.ident;
+                let bindings = bind_fields(&variant.fields);
+                let pattern = destructure(&quote!(Self::#ident), &variant.fields, &bindings);
+                let hash = hash_fields(&variant.fields, &bindings)?;
+                arms.push(quote! {
+                    #pattern => {
+                        #main::Checksum::checksum(&#index, hasher);
+                        #hash
+                    }
+                });
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "`Checksum` can't be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #main::Checksum for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn checksum(&self, hasher: &mut #main::ChecksumHasher) {
+                #body
+            }
+        }
+    })
+}
+
+fn add_bound(generics: &Generics, bound: &Path) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+fn bind_fields(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len())
+        .map(|index| format_ident!("field_{index}", span = Span::call_site()))
+        .collect()
+}
+
+fn destructure(path: &TokenStream2, fields: &Fields, bindings: &[Ident]) -> TokenStream2 {
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => quote!(#path),
+    }
+}
+
+fn write_fields(fields: &Fields, bindings: &[Ident]) -> Result<TokenStream2> {
+    let main = core_path();
+    let mut writes = Vec::new();
+    for (field, binding) in fields.iter().zip(bindings) {
+        if !is_skipped(&field.attrs, "snapshot")? {
+            writes.push(quote!(#main::Snapshot::write_snapshot(#binding, writer);));
+        }
+    }
+    Ok(quote!(#(#writes)*))
+}
+
+fn read_fields(path: &TokenStream2, fields: &Fields) -> Result<TokenStream2> {
+    let main = core_path();
+    let mut reads = Vec::new();
+    for field in fields {
+        reads.push(if is_skipped(&field.attrs, "snapshot")? {
+            quote!(::core::default::Default::default())
+        } else {
+            quote!(#main::Snapshot::read_snapshot(reader))
+        });
+    }
+    // Fields are evaluated in order, which is the order they were written in.
+    Ok(match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #reads),* })
+        }
+        Fields::Unnamed(_) => quote!(#path ( #(#reads),* )),
+        Fields::Unit => quote!(#path),
+    })
+}
+
+fn hash_fields(fields: &Fields, bindings: &[Ident]) -> Result<TokenStream2> {
+    let main = core_path();
+    let mut hashes = Vec::new();
+    for (field, binding) in fields.iter().zip(bindings) {
+        if !is_skipped(&field.attrs, "checksum")? {
+            hashes.push(quote!(#main::Checksum::checksum(#binding, hasher);));
+        }
+    }
+    Ok(quote!(#(#hashes)*))
+}
+
+fn is_skipped(attrs: &[syn::Attribute], name: &str) -> Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(name)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error(format!("unknown `{name}` attribute")))
+            }
+        })?;
+    }
+    Ok(skip)
+}
@@ -29,9 +29,9 @@ fn assert_is_normalized(message: &str, length_squared: Fx) {
         #[cfg(feature = "std")]
         #[expect(clippy::print_stderr, reason = "Allowed behind `std` feature gate.")]
         {
-            eprintln!(
+            std::eprintln!(
                 "Warning: {message} The length is {}.",
-                ops::sqrt(length_squared)
+                Fx::sqrt(length_squared)
             );
         }
     }
@@ -1,10 +1,26 @@
-use std::ops::Mul;
+use core::ops::Mul;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{FVec3, Fx, IntoFx, fvec4::FVec4, fx};
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Reflect,
+    Serialize,
+    Deserialize,
+    Default,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[reflect(opaque)]
+#[reflect(Serialize, Deserialize)]
 pub struct FQuat {
     pub x: Fx,
     pub y: Fx,
@@ -1,7 +1,4 @@
-use std::{
-    f32,
-    ops::{Add, AddAssign, Div, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
-};
+use core::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -1,12 +1,16 @@
 #![allow(clippy::inline_always)]
+// Restriction lint, enabled so the `expect` on the normalization warning stays checked.
+#![warn(clippy::print_stderr)]
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
 
 mod direction;
 mod fquat;
 mod fvec3;
 mod fvec4;
 
-use std::f32;
-
 pub use direction::FDir3;
 pub use fquat::FQuat;
 pub use fvec3::FVec3;
@@ -1,12 +1,21 @@
-use bevy::prelude::*;
+use bevy::{ecs::entity::MapEntities, prelude::*};
+use serde::{Deserialize, Serialize};
 use strum::EnumCount;
-use whitelace_core::{map::Map, math::FVec3};
+use whitelace_core::{
+    main::{Checksum, Snapshot},
+    map::Map,
+    math::FVec3,
+};
 use whitelace_math::{Fx, fx};
 use whitelace_transform::FixedTransform;
 
 use crate::prelude::CollisionSide;
 
-#[derive(Component, Debug, Clone, PartialEq, Eq)]
+#[derive(
+    Component, Reflect, Serialize, Deserialize, Snapshot, Checksum, Debug, Clone, PartialEq, Eq,
+)]
+#[reflect(opaque)]
+#[reflect(Component, Default, Serialize, Deserialize)]
 #[require(FixedTransform)]
 pub struct Collider {
     pub trigger: bool,
@@ -16,6 +25,7 @@ pub struct Collider {
     pub size: FVec3,
     pub material: ColliderMaterial,
 
+    #[entities]
     pub(crate) contacts: Contacts,
 }
 
@@ -120,7 +130,7 @@ impl Collider {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Snapshot, Checksum, Debug, Clone, PartialEq, Eq)]
 pub struct SurfaceContact {
     pub entity: Entity,
     pub contact_point: FVec3,
@@ -131,12 +141,30 @@ pub struct SurfaceContact {
     pub side: CollisionSide,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Snapshot, Checksum, Default, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct Contacts {
     pub(crate) map: Map<Entity, SurfaceContact>,
     count: [usize; CollisionSide::COUNT],
 }
 
+impl MapEntities for SurfaceContact {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.entity = entity_mapper.get_mapped(self.entity);
+    }
+}
+
+impl MapEntities for Contacts {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        self.map = core::mem::take(&mut self.map)
+            .into_iter()
+            .map(|(other, mut contact)| {
+                contact.map_entities(entity_mapper);
+                (entity_mapper.get_mapped(other), contact)
+            })
+            .collect();
+    }
+}
+
 impl Contacts {
     fn insert_other(&mut self, other: Entity, contact: SurfaceContact) {
         let side = contact.side;
@@ -159,7 +187,9 @@ impl Contacts {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Serialize, Deserialize, Snapshot, Checksum, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 pub struct ColliderMaterial {
     /// Коэффициент трения (0-1)
     /// 0 = абсолютно гладкий (лед), 1 = очень шероховатый
@@ -6,7 +6,10 @@ mod side;
 mod substep;
 
 use bevy::prelude::*;
-use whitelace_core::math::{FVec3, Fx, fx};
+use whitelace_core::{
+    main::SortedQuery,
+    math::{FVec3, Fx, fx},
+};
 use whitelace_time::Time;
 use whitelace_transform::{FixedGlobalTransform, FixedTransform};
 
@@ -88,7 +91,7 @@ fn get_velocity(entity: Entity, rigidbodies: &mut Query<&Rigidbody>) -> FVec3 {
 pub(crate) fn apply_physics(
     mut commands: Commands,
     time: Res<Time>,
-    mut colliders: Query<(Entity, &FixedGlobalTransform, &mut Collider)>,
+    mut colliders: SortedQuery<(Entity, &FixedGlobalTransform, &mut Collider)>,
     mut rigidbodies: Query<&Rigidbody>,
     mut positions: Query<&mut FixedTransform>,
 ) {
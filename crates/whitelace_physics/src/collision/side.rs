@@ -1,7 +1,25 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumCount};
+use whitelace_core::main::{Checksum, Snapshot};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, EnumCount, Display)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+    Snapshot,
+    Checksum,
+    EnumCount,
+    Display,
+)]
 pub enum CollisionSide {
     Left = 0b00_0001,   // -X
     Right = 0b00_0010,  // +X
@@ -0,0 +1,54 @@
+use bevy::{
+    color::palettes::css::GREEN,
+    ecs::{
+        resource::Resource,
+        system::{Query, Res},
+    },
+    gizmos::gizmos::Gizmos,
+    transform::components::Transform,
+};
+use whitelace_math::{FQuat, FVec3, const_fx};
+use whitelace_sync::{SyncTarget, WorldLabel, Worlds};
+use whitelace_transform::FixedGlobalTransform;
+
+use crate::prelude::Collider;
+
+#[derive(Resource, Default)]
+pub struct PhysicsDebugManager {
+    pub draw_collider_lines: bool,
+}
+
+pub(crate) fn draw_collider_debug_lines<W: WorldLabel + Default>(
+    manager: Res<PhysicsDebugManager>,
+    mut gizmos: Gizmos,
+    worlds: Res<Worlds>,
+    query: Query<&SyncTarget>,
+) {
+    if !manager.draw_collider_lines {
+        return;
+    }
+
+    let Some(world) = worlds.world(W::default()) else {
+        return;
+    };
+    for sync_target in query {
+        let Ok(entity) = world.get_entity(sync_target.0) else {
+            continue;
+        };
+        let Some(collider) = entity.get::<Collider>() else {
+            continue;
+        };
+
+        let fixed_transform = entity.get::<FixedGlobalTransform>().unwrap();
+        let (pos, rotation, size): (FVec3, FQuat, FVec3) =
+            collider.transform(&fixed_transform.as_local()).into();
+        let position = (pos + size / const_fx!(2)).as_vec3();
+
+        gizmos.cube(
+            Transform::from_translation(position)
+                .with_scale(size.as_vec3())
+                .with_rotation(rotation.as_quat()),
+            GREEN,
+        );
+    }
+}
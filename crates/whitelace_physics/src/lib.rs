@@ -2,38 +2,62 @@
 #![no_std]
 
 pub mod collision;
+#[cfg(feature = "debug_draw")]
+mod debug;
 mod rigidbody;
 
-use bevy::{
-    app::{Plugin, Update},
-    color::palettes::css::GREEN,
-    ecs::{
-        resource::Resource,
-        system::{Query, Res},
-    },
-    gizmos::gizmos::Gizmos,
-    transform::components::Transform,
-};
+use bevy::{app::Plugin, ecs::schedule::IntoScheduleConfigs};
+#[cfg(feature = "debug_draw")]
+pub use debug::PhysicsDebugManager;
 pub use rigidbody::Rigidbody;
 pub(crate) use rigidbody::{apply_material_friction, apply_velocity};
-use whitelace_math::{FQuat, FVec3, const_fx};
 
 pub mod prelude {
     pub use super::{Rigidbody, collision::prelude::*};
 }
 
-use whitelace_core::main::schedule::Physics;
-use whitelace_sync::{MultiworldApp, SyncTarget, WorldLabel, Worlds};
-use whitelace_time::TimePlugin;
-use whitelace_transform::{FixedGlobalTransform, TransformPlugin};
+use whitelace_core::main::{DPlugin, Subworld, input::UserInput, schedule::Physics};
+use whitelace_sync::{MultiworldApp, WorldLabel};
+use whitelace_time::{TimeDPlugin, TimePlugin};
+use whitelace_transform::{TransformDPlugin, TransformPlugin};
+
+use crate::{
+    collision::Collider,
+    prelude::{apply_physics, block_rigidbody_movement_along_normal},
+};
+
+/// Deterministic part of [`PhysicsPlugin`], installed directly into a [`Subworld`].
+#[derive(Default)]
+pub struct PhysicsDPlugin;
+
+impl<I: UserInput> DPlugin<I> for PhysicsDPlugin {
+    fn build(&self, world: &mut Subworld<I>) {
+        world
+            .require_plugin::<TimeDPlugin>()
+            .require_plugin::<TransformDPlugin>();
 
-use crate::prelude::{Collider, apply_physics, block_rigidbody_movement_along_normal};
+        world.add_systems(
+            Physics,
+            (apply_physics, apply_material_friction, apply_velocity).chain(),
+        );
+        world.add_observer(block_rigidbody_movement_along_normal);
+        world
+            .register_entity_mapping::<Collider>()
+            .register_type::<Collider>()
+            .register_type::<Rigidbody>()
+            .register_snapshot::<Collider>()
+            .register_snapshot::<Rigidbody>()
+            .register_checksum::<Collider>()
+            .register_checksum::<Rigidbody>();
+    }
+}
 
-pub struct PhysicsPlugin<W: WorldLabel + Default> {
-    _phantom: core::marker::PhantomData<W>,
+/// Adds [`PhysicsDPlugin`] to the world `W`, whose input type is `I`.
+pub struct PhysicsPlugin<W: WorldLabel + Default, I: UserInput = ()> {
+    _phantom: core::marker::PhantomData<(W, I)>,
 }
 
-impl<W: WorldLabel + Default> Default for PhysicsPlugin<W> {
+impl<W: WorldLabel + Default, I: UserInput> Default for PhysicsPlugin<W, I> {
     fn default() -> Self {
         Self {
             _phantom: core::marker::PhantomData,
@@ -41,64 +65,27 @@ impl<W: WorldLabel + Default> Default for PhysicsPlugin<W> {
     }
 }
 
-impl<W: WorldLabel + Default> Plugin for PhysicsPlugin<W> {
+impl<W: WorldLabel + Default, I: UserInput> Plugin for PhysicsPlugin<W, I> {
     fn build(&self, app: &mut bevy::app::App) {
-        let is_transform_added = app.is_plugin_added::<TransformPlugin<W>>();
-        let is_time_added = app.is_plugin_added::<TimePlugin<W>>();
+        let is_transform_added = app.is_plugin_added::<TransformPlugin<W, I>>();
+        let is_time_added = app.is_plugin_added::<TimePlugin<W, I>>();
 
         if !is_time_added {
-            app.add_plugins(TimePlugin::<W>::default());
+            app.add_plugins(TimePlugin::<W, I>::default());
         }
 
         if !is_transform_added {
-            app.add_plugins(TransformPlugin::<W>::default());
+            app.add_plugins(TransformPlugin::<W, I>::default());
         }
 
-        app.add_world_systems(
-            W::default(),
-            Physics,
-            (apply_physics, apply_material_friction, apply_velocity),
-        );
-
-        app.add_world_observer(W::default(), block_rigidbody_movement_along_normal);
+        app.modify_world_with_input::<I>(W::default(), |world| {
+            world.require_plugin::<PhysicsDPlugin>();
+        });
 
-        app.init_resource::<PhysicsDebugManager>();
-        app.add_systems(Update, draw_collider_debug_lines::<W>);
-    }
-}
-
-#[derive(Resource, Default)]
-pub struct PhysicsDebugManager {
-    pub draw_collider_lines: bool,
-}
-
-fn draw_collider_debug_lines<W: WorldLabel + Default>(
-    manager: Res<PhysicsDebugManager>,
-    mut gizmos: Gizmos,
-    worlds: Res<Worlds>,
-    query: Query<&SyncTarget>,
-) {
-    if !manager.draw_collider_lines {
-        return;
-    }
-
-    let world = worlds.get(W::default()).unwrap();
-    for sync_target in query {
-        let entity = world.get_entity(sync_target.0).unwrap();
-        let Some(collider) = entity.get::<Collider>() else {
-            continue;
-        };
-
-        let fixed_transform = entity.get::<FixedGlobalTransform>().unwrap();
-        let (pos, rotation, size): (FVec3, FQuat, FVec3) =
-            collider.transform(&fixed_transform.as_local()).into();
-        let position = (pos + size / const_fx!(2)).as_vec3();
-
-        gizmos.cube(
-            Transform::from_translation(position)
-                .with_scale(size.as_vec3())
-                .with_rotation(rotation.as_quat()),
-            GREEN,
-        );
+        #[cfg(feature = "debug_draw")]
+        {
+            app.init_resource::<PhysicsDebugManager>();
+            app.add_systems(bevy::app::Update, debug::draw_collider_debug_lines::<W>);
+        }
     }
 }
@@ -1,5 +1,9 @@
 use bevy::prelude::*;
-use whitelace_core::math::{FVec3, Fx, IntoFx, fx};
+use serde::{Deserialize, Serialize};
+use whitelace_core::{
+    main::{Checksum, Snapshot},
+    math::{FVec3, Fx, IntoFx, fx},
+};
 use whitelace_time::Time;
 use whitelace_transform::FixedTransform;
 
@@ -7,7 +11,21 @@ use crate::collision::Collider;
 
 //TODO firction
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Snapshot,
+    Checksum,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
 pub enum BodyType {
     #[default]
     Static,
@@ -15,7 +33,23 @@ pub enum BodyType {
     Kinematic,
 }
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Component,
+    Reflect,
+    Serialize,
+    Deserialize,
+    Snapshot,
+    Checksum,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[reflect(opaque)]
+#[reflect(Component, Default, Serialize, Deserialize)]
 #[require(Collider)]
 pub struct Rigidbody {
     pub body: BodyType,
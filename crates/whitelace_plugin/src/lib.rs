@@ -1,21 +1,15 @@
 #![allow(clippy::needless_pass_by_value)]
 
 use bevy::app::{App, Plugin};
-use whitelace_physics::PhysicsPlugin;
+use whitelace_core::main::{DPluginGroup, DPluginGroupBuilder, input::UserInput};
+use whitelace_physics::{PhysicsDPlugin, PhysicsPlugin};
 use whitelace_sync::{MultiworldApp, SyncPlugin, WorldLabel};
-use whitelace_time::TimePlugin;
-use whitelace_transform::TransformPlugin;
+use whitelace_tilemap::TilemapDPlugin;
+use whitelace_time::{TimeDPlugin, TimePlugin};
+use whitelace_transform::{TransformDPlugin, TransformPlugin};
 
-#[derive(Default, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(WorldLabel, Default, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct LogicWorld;
-impl WorldLabel for LogicWorld {
-    #[doc = r" Clones this `"]
-    #[doc = stringify!(WorldLabel)]
-    #[doc = r"`."]
-    fn dyn_clone(&self) -> Box<dyn WorldLabel> {
-        Box::new(LogicWorld)
-    }
-}
 
 pub struct WhitelacePlugin;
 impl Plugin for WhitelacePlugin {
@@ -28,3 +22,15 @@ impl Plugin for WhitelacePlugin {
         app.add_plugins(PhysicsPlugin::<LogicWorld>::default());
     }
 }
+
+/// Every deterministic plugin, without any main-world syncing. Can be added to any `Subworld`.
+pub struct DeterministicPlugins;
+impl<I: UserInput> DPluginGroup<I> for DeterministicPlugins {
+    fn build(self) -> DPluginGroupBuilder<I> {
+        DPluginGroupBuilder::start::<Self>()
+            .add(TimeDPlugin)
+            .add(TransformDPlugin)
+            .add(PhysicsDPlugin)
+            .add(TilemapDPlugin)
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use whitelace_core::{
+    main::Subworld,
+    math::{FVec3, Fx},
+};
+use whitelace_physics::Rigidbody;
+use whitelace_plugin::DeterministicPlugins;
+use whitelace_tilemap::{
+    Chunk, TileIndex, TilePadding, TilePosition, TileSize, TilemapBundle, TilemapSize,
+    TilemapStorage,
+};
+use whitelace_transform::FixedTransform;
+
+fn populated() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world.add_plugins(DeterministicPlugins);
+
+    let size = TilemapSize::new(20, 2);
+    let mut storage = TilemapStorage::new(&size);
+    let map = world.spawn_empty().id();
+    let mut commands = world.commands();
+    for x in 0..20 {
+        storage.create_tile(
+            20,
+            TilePosition::new(x, 0),
+            TileIndex(x),
+            map,
+            &mut commands,
+        );
+    }
+    world.flush();
+    world.entity_mut(map).insert((
+        TilemapBundle {
+            storage,
+            map_size: size,
+            tile_size: TileSize::new(1.0, 1.0),
+            tile_padding: TilePadding::default(),
+        },
+        FixedTransform::default(),
+    ));
+    world.spawn((
+        FixedTransform {
+            position: FVec3::new(Fx::from_num(3), Fx::from_num(4), Fx::ZERO),
+            ..default()
+        },
+        Rigidbody::dynamic(1),
+    ));
+    world.tick(Vec::new());
+    world
+}
+
+fn chunks(world: &mut Subworld) -> usize {
+    world.query::<&Chunk>().iter(world).count()
+}
+
+#[test]
+fn forks_tick_like_the_original() {
+    let mut world = populated();
+    let mut fork = world.fork();
+    assert_eq!(fork.checksum(), world.checksum());
+    for _ in 0..10 {
+        world.tick(Vec::new());
+        fork.tick(Vec::new());
+        assert_eq!(fork.checksum(), world.checksum());
+    }
+    assert_eq!(chunks(&mut fork), chunks(&mut world));
+}
+
+#[test]
+fn reset_forks_tick_like_the_original() {
+    let mut world = populated();
+    let mut fork = world.fork();
+    for _ in 0..5 {
+        fork.tick(Vec::new());
+    }
+    world.tick(Vec::new());
+
+    world.reset_fork(&mut fork);
+    assert_eq!(fork.checksum(), world.checksum());
+    for _ in 0..5 {
+        world.tick(Vec::new());
+        fork.tick(Vec::new());
+        assert_eq!(fork.checksum(), world.checksum());
+    }
+    assert_eq!(chunks(&mut fork), chunks(&mut world));
+}
+
+#[test]
+fn forks_are_independent() {
+    let world = populated();
+    let before = world.checksum();
+    let mut fork = world.fork();
+    fork.tick(Vec::new());
+    assert_eq!(world.checksum(), before);
+    assert_ne!(fork.checksum(), before);
+}
+
+#[test]
+fn restored_tilemaps_match() {
+    let mut world = populated();
+    let snapshot = world.snapshot();
+    let mut checksums = Vec::new();
+    for _ in 0..5 {
+        world.tick(Vec::new());
+        checksums.push(world.checksum());
+    }
+    world.restore(&snapshot);
+    for checksum in checksums {
+        world.tick(Vec::new());
+        assert_eq!(world.checksum(), checksum);
+    }
+    assert_eq!(chunks(&mut world), 2);
+}
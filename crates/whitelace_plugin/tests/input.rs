@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use whitelace_core::main::{
+    Subworld,
+    input::{FrameInput, UserInput},
+    schedule::{FixedUpdate, SimTick},
+};
+use whitelace_physics::{PhysicsDPlugin, PhysicsPlugin};
+use whitelace_plugin::{DeterministicPlugins, LogicWorld};
+use whitelace_sync::{MultiworldApp, SyncPlugin, Worlds};
+use whitelace_tilemap::{TilemapDPlugin, TilemapPlugin};
+use whitelace_time::TimeDPlugin;
+use whitelace_transform::TransformDPlugin;
+
+#[derive(Debug, Clone, Copy)]
+struct Move(i32);
+
+impl UserInput for Move {}
+
+#[derive(Resource, Default)]
+struct Distance(i32);
+
+fn apply_moves(input: Res<FrameInput<Move>>, mut distance: ResMut<Distance>) {
+    for step in input.get() {
+        distance.0 += step.0;
+    }
+}
+
+#[test]
+fn deterministic_plugins_take_any_input() {
+    let mut world = Subworld::<Move>::default();
+    world.add_plugins(DeterministicPlugins);
+    world.init_resource::<Distance>();
+    world.add_systems(FixedUpdate, apply_moves);
+    world.tick(vec![Move(2), Move(3)]);
+
+    assert!(world.is_plugin_added::<PhysicsDPlugin>());
+    assert!(world.is_plugin_added::<TilemapDPlugin>());
+    assert_eq!(world.resource::<Distance>().0, 5);
+}
+
+#[test]
+fn plugins_install_into_typed_worlds() {
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin)
+        .add_world_with_input::<Move>(LogicWorld)
+        .add_plugins((
+            PhysicsPlugin::<LogicWorld, Move>::default(),
+            TilemapPlugin::<LogicWorld, Move>::default(),
+        ));
+
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    let world = worlds.get_with_input_mut::<Move>(LogicWorld).unwrap();
+    assert!(world.is_plugin_added::<TimeDPlugin>());
+    assert!(world.is_plugin_added::<TransformDPlugin>());
+    assert!(world.is_plugin_added::<PhysicsDPlugin>());
+    assert!(world.is_plugin_added::<TilemapDPlugin>());
+    world.tick(vec![Move(1)]);
+    assert_eq!(world.resource::<SimTick>().get(), 1);
+}
+
+#[test]
+fn tilemaps_pull_in_physics() {
+    let mut world = Subworld::<Move>::default();
+    world.add_plugin(TilemapDPlugin);
+    assert!(world.is_plugin_added::<PhysicsDPlugin>());
+    assert!(world.is_plugin_added::<TransformDPlugin>());
+
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin)
+        .add_world(LogicWorld)
+        .add_plugins(TilemapPlugin::<LogicWorld>::default());
+    assert!(app.is_plugin_added::<PhysicsPlugin<LogicWorld>>());
+}
+
+#[test]
+#[should_panic = "has the input type"]
+fn plugins_check_the_input_type() {
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin)
+        .add_world_with_input::<Move>(LogicWorld)
+        .add_plugins(TilemapPlugin::<LogicWorld>::default());
+}
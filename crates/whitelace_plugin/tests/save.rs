@@ -0,0 +1,156 @@
+#![cfg(feature = "serialize")]
+
+use bevy::prelude::*;
+use whitelace_core::{
+    main::{ScheduledCommands, StableId, Subworld, schedule::SimTick},
+    math::{FVec3, Fx},
+};
+use whitelace_plugin::DeterministicPlugins;
+use whitelace_tilemap::{
+    Chunk, ChunkStorage, TileIndex, TilePadding, TilePosition, TileSize, TilemapBundle,
+    TilemapSize, TilemapStorage,
+};
+use whitelace_transform::FixedTransform;
+
+fn world() -> Subworld {
+    let mut world = Subworld::<()>::default();
+    world.add_plugins(DeterministicPlugins);
+    world
+}
+
+fn populated() -> Subworld {
+    let mut world = world();
+    let parent = world
+        .spawn(FixedTransform {
+            position: FVec3::new(Fx::from_bits(123_456_789), Fx::from_num(2), Fx::ZERO),
+            ..default()
+        })
+        .id();
+    world.spawn((
+        FixedTransform {
+            position: FVec3::new(Fx::from_bits(-7), Fx::ZERO, Fx::ZERO),
+            ..default()
+        },
+        ChildOf(parent),
+    ));
+
+    let size = TilemapSize::new(20, 2);
+    let mut storage = TilemapStorage::new(&size);
+    let map = world.spawn_empty().id();
+    let mut commands = world.commands();
+    for x in 0..20 {
+        storage.create_tile(
+            20,
+            TilePosition::new(x, 0),
+            TileIndex(x),
+            map,
+            &mut commands,
+        );
+    }
+    world.flush();
+    world.entity_mut(map).insert((
+        TilemapBundle {
+            storage,
+            map_size: size,
+            tile_size: TileSize::new(1.0, 1.0),
+            tile_padding: TilePadding::default(),
+        },
+        FixedTransform::default(),
+    ));
+
+    for _ in 0..3 {
+        world.tick(Vec::new());
+    }
+    world
+}
+
+fn position_bits(world: &mut Subworld) -> Vec<(u64, [i64; 3])> {
+    let mut bits: Vec<_> = world
+        .query::<(&StableId, &FixedTransform)>()
+        .iter(world)
+        .map(|(id, transform)| {
+            let position = transform.position;
+            (
+                id.to_raw(),
+                [
+                    position.x.to_bits(),
+                    position.y.to_bits(),
+                    position.z.to_bits(),
+                ],
+            )
+        })
+        .collect();
+    bits.sort_unstable();
+    bits
+}
+
+fn chunk_ids(world: &mut Subworld) -> Vec<Vec<u64>> {
+    let mut chunks: Vec<_> = world
+        .query::<&ChunkStorage>()
+        .iter(world)
+        .map(|storage| {
+            storage
+                .iter()
+                .map(|chunk| world.get::<StableId>(chunk).unwrap().to_raw())
+                .collect()
+        })
+        .collect();
+    chunks.sort_unstable();
+    chunks
+}
+
+#[test]
+fn load_is_bit_exact() {
+    let mut original = populated();
+    let ron = original.save_ron().unwrap();
+
+    let mut loaded = world();
+    loaded.load_ron(&ron).unwrap();
+    assert_eq!(*loaded.resource::<SimTick>(), SimTick::new(3));
+    assert_eq!(loaded.checksum(), original.checksum());
+    assert_eq!(position_bits(&mut loaded), position_bits(&mut original));
+    assert_eq!(chunk_ids(&mut loaded), chunk_ids(&mut original));
+    assert!(!chunk_ids(&mut loaded).is_empty());
+    assert_eq!(
+        loaded.query::<&Chunk>().iter(&loaded).count(),
+        original.query::<&Chunk>().iter(&original).count()
+    );
+
+    for _ in 0..3 {
+        original.tick(Vec::new());
+        loaded.tick(Vec::new());
+        assert_eq!(loaded.checksum(), original.checksum());
+    }
+}
+
+#[test]
+fn saves_are_stable() {
+    let world = populated();
+    assert_eq!(world.save_ron().unwrap(), world.save_ron().unwrap());
+}
+
+#[test]
+fn loading_replaces_the_world() {
+    let ron = populated().save_ron().unwrap();
+    let mut fresh = world();
+    fresh.load_ron(&ron).unwrap();
+
+    let mut reloaded = populated();
+    reloaded.tick(Vec::new());
+    reloaded.load_ron(&ron).unwrap();
+    assert_eq!(reloaded.checksum(), fresh.checksum());
+    assert_eq!(position_bits(&mut reloaded), position_bits(&mut fresh));
+}
+
+#[test]
+fn pending_commands_prevent_saving() {
+    let mut world = populated();
+    let id = world
+        .resource_mut::<ScheduledCommands>()
+        .schedule_in(1, |_: &mut World| {});
+    let error = world.save_ron().unwrap_err();
+    assert!(error.to_string().contains("1 pending ScheduledCommands"));
+
+    world.resource_mut::<ScheduledCommands>().cancel(id);
+    assert!(world.save_ron().is_ok());
+}
@@ -0,0 +1,98 @@
+use bevy::{
+    ecs::{
+        component::ComponentId,
+        intern::Interned,
+        query::{AccessConflicts, FilteredAccessSet, QueryData, QueryFilter},
+    },
+    platform::prelude::{String, ToString, Vec},
+    prelude::*,
+};
+use whitelace_core::map::Map;
+
+use crate::WorldLabel;
+
+/// The accesses of the params of a sync system, per world.
+///
+/// Params borrow parts of the same world, so the params of a system are checked against each
+/// other when the system is added, like bevy does for regular systems.
+pub struct SyncAccess<'a> {
+    system: &'static str,
+    /// Components and resources are registered here to get ids to compare, the ids of the
+    /// actual worlds don't exist yet when the system is added. Shared by all the sync systems.
+    ids: &'a mut World,
+    worlds: Map<Interned<dyn WorldLabel>, FilteredAccessSet>,
+}
+
+impl<'a> SyncAccess<'a> {
+    pub(crate) fn new(system: &'static str, ids: &'a mut World) -> Self {
+        Self {
+            system,
+            ids,
+            worlds: Map::default(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if a previous param writes `R` in the world.
+    pub fn add_resource_read<R: Resource>(&mut self, label: impl WorldLabel) {
+        let id = self.ids.register_resource::<R>();
+        let access = self.worlds.entry(label.intern()).or_default();
+        assert!(
+            !access.combined_access().has_resource_write(id),
+            "WorldRes<{}> in sync system {} conflicts with a previous WorldResMut<{0}> access in world {:?}",
+            core::any::type_name::<R>(),
+            self.system,
+            label,
+        );
+        access.add_unfiltered_resource_read(id);
+    }
+
+    /// # Panics
+    ///
+    /// Panics if a previous param reads or writes `R` in the world.
+    pub fn add_resource_write<R: Resource>(&mut self, label: impl WorldLabel) {
+        let id = self.ids.register_resource::<R>();
+        let access = self.worlds.entry(label.intern()).or_default();
+        let combined = access.combined_access();
+        assert!(
+            !combined.has_resource_read(id) && !combined.has_resource_write(id),
+            "WorldResMut<{}> in sync system {} conflicts with a previous access of {0} in world {:?}",
+            core::any::type_name::<R>(),
+            self.system,
+            label,
+        );
+        access.add_unfiltered_resource_write(id);
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the query conflicts with a previous param in the world.
+    pub fn add_query<D: QueryData, F: QueryFilter>(&mut self, label: impl WorldLabel) {
+        let state = QueryState::<D, F>::new(self.ids);
+        let access = self.worlds.entry(label.intern()).or_default();
+        let conflicts = access.get_conflicts_single(state.component_access());
+        assert!(
+            conflicts.is_empty(),
+            "WorldQuery<{}, {}> in sync system {} accesses {} in a way that conflicts with a previous param in world {:?}. Consider `Without<T>` filters to make the queries disjoint.",
+            core::any::type_name::<D>(),
+            core::any::type_name::<F>(),
+            self.system,
+            conflict_names(self.ids, &conflicts),
+            label,
+        );
+        access.add(state.component_access().clone());
+    }
+}
+
+fn conflict_names(ids: &World, conflicts: &AccessConflicts) -> String {
+    match conflicts {
+        AccessConflicts::All => "all components".to_string(),
+        AccessConflicts::Individual(conflicts) => conflicts
+            .ones()
+            .filter_map(|id| ids.components().get_name(ComponentId::new(id)))
+            .map(|name| name.shortname().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
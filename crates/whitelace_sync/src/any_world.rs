@@ -0,0 +1,73 @@
+use core::any::Any;
+
+use bevy::{
+    ecs::{
+        schedule::{InternedScheduleLabel, ScheduleConfigs},
+        system::ScheduleSystem,
+    },
+    platform::prelude::{Box, Vec},
+    prelude::*,
+};
+use whitelace_core::main::{Subworld, input::UserInput, schedule::SubworldExecutor};
+
+/// A [`Subworld`] of any input type, as stored in [`Worlds`](crate::Worlds).
+///
+/// Cast to `&dyn Any` to get the typed world back, or use
+/// [`Worlds::get_with_input`](crate::Worlds::get_with_input).
+pub trait AnySubworld: Any + Send + Sync {
+    fn world(&self) -> &World;
+
+    fn world_mut(&mut self) -> &mut World;
+
+    fn input_type_name(&self) -> &'static str;
+
+    /// Runs a tick with no input.
+    fn tick_without_input(&mut self);
+
+    /// See [`Subworld::executor`].
+    fn executor(&self) -> SubworldExecutor;
+
+    /// See [`Subworld::rebuild`].
+    fn rebuild(&self) -> Box<dyn AnySubworld>;
+
+    /// See [`Subworld::add_systems`].
+    fn add_schedule_configs(
+        &mut self,
+        schedule: InternedScheduleLabel,
+        systems: ScheduleConfigs<ScheduleSystem>,
+    );
+}
+
+impl<I: UserInput> AnySubworld for Subworld<I> {
+    fn world(&self) -> &World {
+        self
+    }
+
+    fn world_mut(&mut self) -> &mut World {
+        self
+    }
+
+    fn input_type_name(&self) -> &'static str {
+        core::any::type_name::<I>()
+    }
+
+    fn tick_without_input(&mut self) {
+        self.tick(Vec::new());
+    }
+
+    fn executor(&self) -> SubworldExecutor {
+        Subworld::executor(self)
+    }
+
+    fn rebuild(&self) -> Box<dyn AnySubworld> {
+        Box::new(Subworld::rebuild(self))
+    }
+
+    fn add_schedule_configs(
+        &mut self,
+        schedule: InternedScheduleLabel,
+        systems: ScheduleConfigs<ScheduleSystem>,
+    ) {
+        self.add_systems(schedule, systems);
+    }
+}
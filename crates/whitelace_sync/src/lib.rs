@@ -3,111 +3,218 @@
 #![allow(clippy::missing_safety_doc)]
 #![allow(clippy::needless_pass_by_value)]
 #![allow(clippy::iter_without_into_iter)]
+#![allow(clippy::type_complexity)]
 #![allow(dead_code)]
 #![no_std]
 
+extern crate alloc;
+extern crate self as whitelace_sync;
+
 use core::{
-    any::TypeId,
-    cell::UnsafeCell,
+    any::{Any, TypeId},
     ops::{Deref, DerefMut},
 };
 
 use bevy::{
     ecs::{
+        change_detection::{ComponentTicks, MAX_CHANGE_AGE, Tick},
+        component::Mutable,
         define_label,
         intern::Interned,
+        lifecycle::HookContext,
         query::{QueryData, QueryFilter, QueryIter},
         schedule::ScheduleLabel,
-        system::{IntoObserverSystem, ScheduleSystem, SystemParam},
+        system::{IntoObserverSystem, RunSystemError, RunSystemOnce, ScheduleSystem, SystemParam},
+        world::{CommandQueue, DeferredWorld, WorldId, unsafe_world_cell::UnsafeWorldCell},
     },
+    log::warn,
     prelude::*,
 };
-use whitelace_core::{main::Subworld, map::Map};
+use whitelace_core::{
+    main::{DPlugin, Subworld, input::UserInput},
+    map::Map,
+};
+pub use whitelace_macros::WorldLabel;
+
+mod access;
+mod any_world;
+mod lifecycle;
+mod mirror;
+mod schedule;
+mod tick;
+pub use access::SyncAccess;
+pub use any_world::AnySubworld;
+pub use lifecycle::{Orphan, OrphanPolicy, handle_orphans};
+use schedule::SyncSystemNode;
+pub use schedule::{IntoSyncSystemConfig, SyncPoint, SyncSet, SyncSystemConfig};
+use tick::WorldEntry;
+pub use tick::{TickBudget, TickStats};
+
+#[doc(hidden)]
+pub mod __macro_exports {
+    pub use bevy::platform::prelude::Box;
+}
 
+/// The entity a visual, a main world entity, is synced to. It lives in the [`SyncWorld`] of the
+/// visual, which has to be inserted along with it.
+///
+/// # Panics
+///
+/// Panics on insertion if the entity has no [`SyncWorld`].
 #[derive(Component, Debug, Deref, DerefMut, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
-pub struct SyncTarget(pub Entity);
+#[component(on_insert = on_insert_sync_target)]
+pub struct SyncTarget(#[entities] pub Entity);
+
+/// Entity ids are only unique within a world, so a target can't be found without its world.
+fn on_insert_sync_target(world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+    assert!(
+        world.entity(entity).contains::<SyncWorld>(),
+        "SyncTarget inserted on {entity} without a SyncWorld, insert both together"
+    );
+}
+
+/// The world a [`SyncTarget`] points into. Entities with it are despawned when their world is
+/// reset or removed with [`MultiworldCommands`].
+#[derive(Component, Debug, Deref, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SyncWorld(pub Interned<dyn WorldLabel>);
 
 #[derive(Default, Resource)]
 pub struct Worlds {
-    inner: Map<Interned<dyn WorldLabel + 'static>, Subworld>,
+    inner: Map<Interned<dyn WorldLabel + 'static>, WorldEntry>,
 }
 
 impl Worlds {
+    /// Shorthand for [`Worlds::get_with_input`] of a world without input.
     pub fn get(&self, label: impl WorldLabel) -> Option<&Subworld> {
-        self.inner.get(&label.intern())
+        self.get_with_input(label)
     }
 
+    /// Shorthand for [`Worlds::get_with_input_mut`] of a world without input.
     pub fn get_mut(&mut self, label: impl WorldLabel) -> Option<&mut Subworld> {
-        self.inner.get_mut(&label.intern())
+        self.get_with_input_mut(label)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the world exists but with another input type than `I`.
+    pub fn get_with_input<I: UserInput>(&self, label: impl WorldLabel) -> Option<&Subworld<I>> {
+        let world = self.inner.get(&label.intern())?.world.as_ref();
+        Some(downcast_ref(world, label.intern()))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the world exists but with another input type than `I`.
+    pub fn get_with_input_mut<I: UserInput>(
+        &mut self,
+        label: impl WorldLabel,
+    ) -> Option<&mut Subworld<I>> {
+        let world = self.inner.get_mut(&label.intern())?.world.as_mut();
+        Some(downcast_mut(world, label.intern()))
+    }
+
+    /// The world with this label, whatever its input type.
+    pub fn world(&self, label: impl WorldLabel) -> Option<&World> {
+        self.inner
+            .get(&label.intern())
+            .map(|entry| entry.world.world())
+    }
+
+    pub fn world_mut(&mut self, label: impl WorldLabel) -> Option<&mut World> {
+        self.inner
+            .get_mut(&label.intern())
+            .map(|entry| entry.world.world_mut())
+    }
+
+    pub fn contains(&self, label: impl WorldLabel) -> bool {
+        self.inner.contains_key(&label.intern())
+    }
+
+    /// Removes a world, keeping the order of the others.
+    pub fn remove(&mut self, label: impl WorldLabel) -> Option<Box<dyn AnySubworld>> {
+        self.inner
+            .shift_remove(&label.intern())
+            .map(|entry| entry.world)
     }
 }
 
-#[derive(Default, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
-pub struct MainWorld;
-impl WorldLabel for MainWorld {
-    #[doc = r" Clones this `"]
-    #[doc = stringify!(WorldLabel)]
-    #[doc = r"`."]
-    fn dyn_clone(&self) -> Box<dyn WorldLabel> {
-        Box::new(MainWorld)
+fn downcast_ref<I: UserInput>(
+    world: &dyn AnySubworld,
+    label: Interned<dyn WorldLabel>,
+) -> &Subworld<I> {
+    let name = world.input_type_name();
+    (world as &dyn Any)
+        .downcast_ref()
+        .unwrap_or_else(|| input_mismatch::<I>(label, name))
+}
+
+fn downcast_mut<I: UserInput>(
+    world: &mut dyn AnySubworld,
+    label: Interned<dyn WorldLabel>,
+) -> &mut Subworld<I> {
+    let name = world.input_type_name();
+    (world as &mut dyn Any)
+        .downcast_mut()
+        .unwrap_or_else(|| input_mismatch::<I>(label, name))
+}
+
+fn input_mismatch<I: UserInput>(label: Interned<dyn WorldLabel>, actual: &str) -> ! {
+    panic!(
+        "World {label:?} has the input type `{actual}`, not `{}`",
+        core::any::type_name::<I>()
+    )
+}
+
+/// Creates a world sharing the type registry of the main world, so types registered there are
+/// covered by the float audit.
+fn new_world<I: UserInput>(main_registry: Option<&AppTypeRegistry>) -> Subworld<I> {
+    let mut world = Subworld::default();
+    if let Some(registry) = main_registry {
+        world.set_type_registry(registry.clone());
     }
+    world
 }
 
+#[derive(WorldLabel, Default, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct MainWorld;
+
 define_label!(WorldLabel, WORLD_LABEL_INTERNER);
 
 pub struct WorldResMut<'worlds, R: Resource, W: WorldLabel = MainWorld> {
     _resource: core::marker::PhantomData<R>,
-    label: W,
-    main: &'worlds mut World,
-    worlds: &'worlds mut Worlds,
+    _label: core::marker::PhantomData<W>,
+    world: UnsafeWorldCell<'worlds>,
 }
 
 impl<W: WorldLabel + Default, R: Resource> WorldSystemParam for WorldResMut<'_, R, W> {
     type State = ();
 
-    fn init_state(_: &mut World) -> Self::State {}
+    fn init_access(access: &mut SyncAccess) {
+        access.add_resource_write::<R>(W::default());
+    }
 
-    unsafe fn get_param((): Self::State, ctx: &SystemContext) -> Self {
-        unsafe {
-            Self {
-                _resource: core::marker::PhantomData,
-                label: W::default(),
-                main: &mut *ctx.main,
-                worlds: &mut *ctx.worlds,
-            }
+    unsafe fn validate_param(ctx: &SystemContext) -> bool {
+        unsafe { ctx.world::<W>() }.is_some()
+    }
+
+    unsafe fn get_param((): &mut Self::State, ctx: &SystemContext) -> Self {
+        Self {
+            _resource: core::marker::PhantomData,
+            _label: core::marker::PhantomData,
+            world: unsafe { ctx.world::<W>() }.expect("World not found"),
         }
     }
 }
 
 impl<R: Resource, W: WorldLabel> WorldResMut<'_, R, W> {
-    /// # Panics
-    ///
-    /// Panics if the world is not found.
     fn get_inner(&self) -> Option<&R> {
-        if TypeId::of::<W>() == TypeId::of::<MainWorld>() {
-            self.main.get_resource::<R>()
-        } else {
-            let world = self
-                .worlds
-                .get(self.label.intern())
-                .expect("World not found");
-            world.get_resource::<R>()
-        }
+        // SAFETY: the param has the only access to `R` in its world, see `SyncAccess`.
+        unsafe { self.world.get_resource::<R>() }
     }
 
-    /// # Panics
-    ///
-    /// Panics if the world is not found.
     fn get_inner_mut(&mut self) -> Option<Mut<'_, R>> {
-        if TypeId::of::<W>() == TypeId::of::<MainWorld>() {
-            self.main.get_resource_mut::<R>()
-        } else {
-            let world = self
-                .worlds
-                .get_mut(self.label.intern())
-                .expect("World not found");
-            world.get_resource_mut::<R>()
-        }
+        // SAFETY: as above, and `&mut self` keeps the returned borrow unique.
+        unsafe { self.world.get_resource_mut::<R>() }
     }
 }
 
@@ -121,41 +228,56 @@ impl<R: Resource, W: WorldLabel> Deref for WorldResMut<'_, R, W> {
 
 impl<R: Resource, W: WorldLabel> DerefMut for WorldResMut<'_, R, W> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        let mut m = self.get_inner_mut().unwrap_or_else(|| {
-            panic!(
-                "Resource '{}' not found in the world '{}'",
-                core::any::type_name::<R>(),
-                core::any::type_name::<W>()
-            )
-        });
-        let mut_ref = m.as_mut();
-        unsafe {
-            let ptr = mut_ref as *mut R;
-            &mut *ptr
-        }
+        self.get_inner_mut()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Resource '{}' not found in the world '{}'",
+                    core::any::type_name::<R>(),
+                    core::any::type_name::<W>()
+                )
+            })
+            .into_inner()
     }
 }
 
 pub struct WorldRes<'worlds, R: Resource, W: WorldLabel = MainWorld> {
     _resource: core::marker::PhantomData<R>,
-    label: W,
-    main: &'worlds mut World,
-    worlds: &'worlds mut Worlds,
+    _label: core::marker::PhantomData<W>,
+    last_run: Tick,
+    this_run: Tick,
+    world: UnsafeWorldCell<'worlds>,
 }
 
 impl<R: Resource, W: WorldLabel> WorldRes<'_, R, W> {
+    fn get_inner(&self) -> Option<&R> {
+        // SAFETY: no param writes `R` in this world, see `SyncAccess`.
+        unsafe { self.world.get_resource::<R>() }
+    }
+
+    /// If the resource was added since the last run of the system.
+    ///
     /// # Panics
     ///
-    /// Panics if the world is not found.
-    fn get_inner(&self) -> Option<&R> {
-        if TypeId::of::<W>() == TypeId::of::<MainWorld>() {
-            self.main.get_resource::<R>()
-        } else {
-            let world = self
-                .worlds
-                .get(self.label.intern())
-                .expect("World not found");
-            world.get_resource::<R>()
+    /// Panics if the resource is not found.
+    pub fn is_added(&self) -> bool {
+        self.change_ticks().is_added(self.last_run, self.this_run)
+    }
+
+    /// If the resource was added or changed since the last run of the system.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource is not found.
+    pub fn is_changed(&self) -> bool {
+        self.change_ticks().is_changed(self.last_run, self.this_run)
+    }
+
+    fn change_ticks(&self) -> ComponentTicks {
+        // SAFETY: as in `get_inner`.
+        let resource = unsafe { self.world.get_resource_ref::<R>() }.expect("Resource not found");
+        ComponentTicks {
+            added: resource.added(),
+            changed: resource.last_changed(),
         }
     }
 }
@@ -173,38 +295,100 @@ where
     R: Resource,
     W: WorldLabel + Default,
 {
-    type State = ();
+    /// The world of the last run and its tick.
+    type State = Option<(WorldId, Tick)>;
 
-    fn init_state(_world: &mut World) -> Self::State {}
+    fn init_access(access: &mut SyncAccess) {
+        access.add_resource_read::<R>(W::default());
+    }
 
-    unsafe fn get_param(_state: Self::State, ctx: &SystemContext) -> Self {
-        unsafe {
-            let main = &mut *ctx.main;
-            let worlds = &mut *ctx.worlds;
-            WorldRes {
-                _resource: core::marker::PhantomData,
-                label: W::default(),
-                main,
-                worlds,
-            }
+    unsafe fn validate_param(ctx: &SystemContext) -> bool {
+        unsafe { ctx.world::<W>() }.is_some()
+    }
+
+    unsafe fn get_param(state: &mut Self::State, ctx: &SystemContext) -> Self {
+        let world = unsafe { ctx.world::<W>() }.expect("World not found");
+        let (last_run, this_run) = run_ticks(state, world);
+        WorldRes {
+            _resource: core::marker::PhantomData,
+            _label: core::marker::PhantomData,
+            last_run,
+            this_run,
+            world,
         }
     }
 }
 
+/// `None` if the world or the resource doesn't exist, like `Option<Res<R>>`.
+impl<R, W> WorldSystemParam for Option<WorldRes<'_, R, W>>
+where
+    R: Resource,
+    W: WorldLabel + Default,
+{
+    type State = Option<(WorldId, Tick)>;
+
+    fn init_access(access: &mut SyncAccess) {
+        WorldRes::<R, W>::init_access(access);
+    }
+
+    unsafe fn get_param(state: &mut Self::State, ctx: &SystemContext) -> Self {
+        unsafe { ctx.world::<W>() }?;
+        let param = unsafe { WorldRes::<R, W>::get_param(state, ctx) };
+        param.get_inner().is_some().then_some(param)
+    }
+}
+
+/// `None` if the world or the resource doesn't exist, like `Option<ResMut<R>>`.
+impl<R, W> WorldSystemParam for Option<WorldResMut<'_, R, W>>
+where
+    R: Resource,
+    W: WorldLabel + Default,
+{
+    type State = ();
+
+    fn init_access(access: &mut SyncAccess) {
+        WorldResMut::<R, W>::init_access(access);
+    }
+
+    unsafe fn get_param(state: &mut Self::State, ctx: &SystemContext) -> Self {
+        unsafe { ctx.world::<W>() }?;
+        let param = unsafe { WorldResMut::<R, W>::get_param(state, ctx) };
+        param.get_inner().is_some().then_some(param)
+    }
+}
+
+/// The ticks a param compares changes against: the last run of the system in `world`, and this
+/// run.
+///
+/// Every run advances the change tick of the world, like a bevy system does, so changes made by
+/// the system are told apart from the ones made before it. A reset world is a new world, so
+/// everything in it counts as changed on the first run after the reset.
+fn run_ticks(state: &mut Option<(WorldId, Tick)>, world: UnsafeWorldCell) -> (Tick, Tick) {
+    let this_run = world.increment_change_tick();
+    let last_run = match *state {
+        Some((id, last_run)) if id == world.id() => last_run,
+        _ => Tick::new(this_run.get().wrapping_sub(MAX_CHANGE_AGE)),
+    };
+    *state = Some((world.id(), this_run));
+    (last_run, this_run)
+}
+
 pub struct WorldQuery<'worlds, D, F = (), W = MainWorld>
 where
     W: WorldLabel,
     D: QueryData,
     F: QueryFilter,
 {
-    _data: core::marker::PhantomData<D>,
-    _filter: core::marker::PhantomData<F>,
-    state: UnsafeCell<QueryState<D, F>>,
-    label: W,
-    main: &'worlds mut World,
-    worlds: &'worlds mut Worlds,
+    _label: core::marker::PhantomData<W>,
+    /// Kept by the system, and up to date with the archetypes of the world, see
+    /// [`WorldSystemParam::update_state`].
+    state: *const QueryState<D, F>,
+    last_run: Tick,
+    this_run: Tick,
+    world: UnsafeWorldCell<'worlds>,
 }
 
+// SAFETY: the state is only read, and `QueryState` is `Send` and `Sync` itself.
 unsafe impl<D, F, W> Send for WorldQuery<'_, D, F, W>
 where
     D: QueryData,
@@ -213,16 +397,13 @@ where
 {
 }
 
+// SAFETY: as for `Send`.
 unsafe impl<D, F, W> Sync for WorldQuery<'_, D, F, W>
 where
     D: QueryData,
     F: QueryFilter,
     W: WorldLabel,
 {
-    // SAFETY:
-    // 1. QueryState<D, F> должен быть Sync
-    // 2. Мы гарантируем, что доступ к UnsafeCell синхронизирован внешне
-    // 3. Нет внутренней мутабельности без синхронизации между потоками
 }
 
 impl<D, F, W> WorldQuery<'_, D, F, W>
@@ -232,25 +413,11 @@ where
     F: QueryFilter,
 {
     pub fn iter(&self) -> QueryIter<'_, '_, <D as QueryData>::ReadOnly, F> {
-        let world = unsafe {
-            let world = self.get_world();
-            let ptr = world as *const World;
-            &*ptr
-        };
-
-        let state = self.get_state_mut();
-        state.iter(world)
+        self.query().into_iter()
     }
 
     pub fn iter_mut(&mut self) -> QueryIter<'_, '_, D, F> {
-        let world = unsafe {
-            let world = self.get_world_mut();
-            let ptr = world as *mut World;
-            &mut *ptr
-        };
-
-        let state = self.get_state_mut();
-        state.iter_mut(world)
+        self.query_mut().into_iter()
     }
 
     pub fn get(
@@ -260,13 +427,7 @@ where
         <<D as QueryData>::ReadOnly as QueryData>::Item<'_, '_>,
         bevy::ecs::query::QueryEntityError,
     > {
-        let world = unsafe {
-            let world = self.get_world();
-            let ptr = world as *const World;
-            &*ptr
-        };
-        let state = self.get_state_mut();
-        state.get(world, entity)
+        self.query().get_inner(entity)
     }
 
     pub fn get_mut(
@@ -274,55 +435,86 @@ where
         entity: Entity,
     ) -> core::result::Result<<D as QueryData>::Item<'_, '_>, bevy::ecs::query::QueryEntityError>
     {
-        let world = unsafe {
-            let world = self.get_world_mut();
-            let ptr = world as *mut World;
-            &mut *ptr
-        };
-
-        let state = self.get_state_mut();
-        state.get_mut(world, entity)
+        self.query_mut().get_inner(entity)
     }
 
-    fn get_world_mut(&mut self) -> &mut World {
-        if TypeId::of::<W>() == TypeId::of::<MainWorld>() {
-            self.main
-        } else {
-            self.worlds
-                .get_mut(self.label.intern())
-                .expect("World not found")
+    /// Change detection compares against the last run of the system, like in bevy's `Query`.
+    fn query(&self) -> Query<'_, '_, <D as QueryData>::ReadOnly, F> {
+        // SAFETY: no other param writes what the query reads, see `SyncAccess`, and the state
+        // outlives the param.
+        unsafe {
+            (*self.state)
+                .as_readonly()
+                .query_unchecked_manual_with_ticks(self.world, self.last_run, self.this_run)
         }
     }
 
-    fn get_world(&self) -> &World {
-        if TypeId::of::<W>() == TypeId::of::<MainWorld>() {
-            self.main
-        } else {
-            self.worlds
-                .get(self.label.intern())
-                .expect("World not found")
+    fn query_mut(&mut self) -> Query<'_, '_, D, F> {
+        // SAFETY: as in `query`, and `&mut self` keeps the mutable items unique.
+        unsafe {
+            (*self.state).query_unchecked_manual_with_ticks(
+                self.world,
+                self.last_run,
+                self.this_run,
+            )
         }
     }
+}
 
-    fn get_state(&self) -> &QueryState<D, F> {
-        unsafe { &*self.state.get() }
-    }
+/// The worlds a sync system runs on, as [`UnsafeWorldCell`]s, so each param only borrows what
+/// it registered in [`SyncAccess`], which makes sure the borrows don't overlap.
+pub struct SystemContext<'w> {
+    main: UnsafeWorldCell<'w>,
+    worlds: Map<Interned<dyn WorldLabel>, UnsafeWorldCell<'w>>,
+}
 
-    #[allow(clippy::mut_from_ref)]
-    fn get_state_mut(&self) -> &mut QueryState<D, F> {
-        unsafe { &mut *self.state.get() }
+impl<'w> SystemContext<'w> {
+    fn new(main: &'w mut World, worlds: &'w mut Worlds) -> Self {
+        Self {
+            main: main.as_unsafe_world_cell(),
+            worlds: worlds
+                .inner
+                .iter_mut()
+                .map(|(label, entry)| (*label, entry.world.world_mut().as_unsafe_world_cell()))
+                .collect(),
+        }
     }
-}
 
-pub struct SystemContext {
-    main: *mut World,
-    worlds: *mut Worlds,
+    /// The world `W`, if it exists.
+    ///
+    /// # Safety
+    ///
+    /// The cell must not be used after the run of the system, and only for the accesses the
+    /// param registered.
+    unsafe fn world<'p, W: WorldLabel + Default>(&self) -> Option<UnsafeWorldCell<'p>> {
+        let world = if TypeId::of::<W>() == TypeId::of::<MainWorld>() {
+            Some(self.main)
+        } else {
+            self.worlds.get(&W::default().intern()).copied()
+        };
+        // SAFETY: the worlds stay borrowed for the whole run, see `SystemContext::new`.
+        world.map(|world| unsafe {
+            core::mem::transmute::<UnsafeWorldCell<'w>, UnsafeWorldCell<'p>>(world)
+        })
+    }
 }
 
 pub trait WorldSystemParam {
-    type State;
-    fn init_state(world: &mut World) -> Self::State;
-    unsafe fn get_param(state: Self::State, ctx: &SystemContext) -> Self;
+    /// Kept by the system between runs.
+    type State: Default + Send + Sync + 'static;
+    /// Registers what the param accesses, panicking if it conflicts with a previous param.
+    fn init_access(access: &mut SyncAccess);
+    /// If the param can be built. Like bevy, a system with a param that can't is skipped, e.g.
+    /// after its world was removed.
+    unsafe fn validate_param(_ctx: &SystemContext) -> bool {
+        true
+    }
+    /// Called before any param of the system is built, so it may borrow its world mutably.
+    unsafe fn update_state(_state: &mut Self::State, _ctx: &SystemContext) {}
+    unsafe fn get_param(state: &mut Self::State, ctx: &SystemContext) -> Self;
+    /// Called once the system ran and its params are dropped, so it may borrow its world
+    /// mutably.
+    unsafe fn apply(_state: &mut Self::State, _ctx: &SystemContext) {}
 }
 
 impl<D, F, W> WorldSystemParam for WorldQuery<'static, D, F, W>
@@ -331,48 +523,128 @@ where
     F: QueryFilter + 'static,
     W: WorldLabel + Default + 'static,
 {
-    type State = QueryState<D, F>;
+    /// Created on the first run, against the queried world, and the ticks of [`run_ticks`].
+    type State = (Option<(WorldId, QueryState<D, F>)>, Option<(WorldId, Tick)>);
 
-    fn init_state(world: &mut World) -> Self::State {
-        world.query_filtered::<D, F>()
+    fn init_access(access: &mut SyncAccess) {
+        access.add_query::<D, F>(W::default());
     }
 
-    unsafe fn get_param(state: Self::State, ctx: &SystemContext) -> Self {
-        unsafe {
-            let main = &mut *ctx.main;
-            let worlds = &mut *ctx.worlds;
-            WorldQuery {
-                _data: core::marker::PhantomData,
-                _filter: core::marker::PhantomData,
-                state: UnsafeCell::new(state),
-                label: W::default(),
-                main,
-                worlds,
-            }
+    unsafe fn validate_param(ctx: &SystemContext) -> bool {
+        unsafe { ctx.world::<W>() }.is_some()
+    }
+
+    unsafe fn update_state((state, _): &mut Self::State, ctx: &SystemContext) {
+        // SAFETY: no param is built yet, so nothing else borrows the world.
+        let world = unsafe { ctx.world::<W>().expect("World not found").world_mut() };
+        // A reset world is a new world, so its archetypes start over.
+        if state.as_ref().is_none_or(|(id, _)| *id != world.id()) {
+            *state = Some((world.id(), world.query_filtered::<D, F>()));
         }
+        state.as_mut().unwrap().1.update_archetypes(world);
+    }
+
+    unsafe fn get_param((state, ticks): &mut Self::State, ctx: &SystemContext) -> Self {
+        let world = unsafe { ctx.world::<W>() }.expect("World not found");
+        let (last_run, this_run) = run_ticks(ticks, world);
+        WorldQuery {
+            _label: core::marker::PhantomData,
+            state: &raw const state.as_ref().unwrap().1,
+            last_run,
+            this_run,
+            world,
+        }
+    }
+}
+
+/// [`Commands`] of a world. They are applied before the next tick of a Subworld, and after
+/// the sync systems for the main world.
+pub struct WorldCommands<'worlds, W: WorldLabel = MainWorld> {
+    _label: core::marker::PhantomData<W>,
+    commands: Commands<'worlds, 'worlds>,
+}
+
+impl<'worlds, W: WorldLabel> Deref for WorldCommands<'worlds, W> {
+    type Target = Commands<'worlds, 'worlds>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.commands
+    }
+}
+
+impl<W: WorldLabel> DerefMut for WorldCommands<'_, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.commands
     }
 }
 
-pub struct FunctionSystem<Marker, F> {
+impl<W: WorldLabel + Default> WorldSystemParam for WorldCommands<'static, W> {
+    /// The commands of the run, moved to the world once the system ran.
+    type State = CommandQueue;
+
+    /// Commands are deferred, so they never conflict with the other params.
+    fn init_access(_access: &mut SyncAccess) {}
+
+    unsafe fn validate_param(ctx: &SystemContext) -> bool {
+        unsafe { ctx.world::<W>() }.is_some()
+    }
+
+    unsafe fn get_param(queue: &mut Self::State, ctx: &SystemContext) -> Self {
+        let world = unsafe { ctx.world::<W>() }.expect("World not found");
+        let queue: *mut CommandQueue = queue;
+        // SAFETY: only this param uses the queue until `apply`, after the run.
+        let queue = unsafe { &mut *queue };
+        WorldCommands {
+            _label: core::marker::PhantomData,
+            commands: Commands::new_from_entities(
+                queue,
+                world.entities_allocator(),
+                world.entities(),
+            ),
+        }
+    }
+
+    unsafe fn apply(queue: &mut Self::State, ctx: &SystemContext) {
+        // SAFETY: the params are dropped, so nothing else borrows the world.
+        let world = unsafe { ctx.world::<W>().expect("World not found").world_mut() };
+        world.commands().append(queue);
+    }
+}
+
+pub struct FunctionSystem<Marker, F, S> {
     func: F,
+    state: S,
     _marker: core::marker::PhantomData<Marker>,
 }
 
 macro_rules! impl_into_world_system {
     ($($P:ident),*) => {
-        impl<F, $($P),*> WorldSyncSystem for FunctionSystem<($($P,)*), F>
+        impl<F, $($P),*> WorldSyncSystem for FunctionSystem<($($P,)*), F, ($($P::State,)*)>
         where
             $($P: WorldSystemParam + Send + Sync + 'static ,)*
             F: FnMut($($P),*) + Send + Sync + 'static,
         {
+            fn init_access(&self, ids: &mut World) {
+                let mut access = SyncAccess::new(core::any::type_name::<F>(), ids);
+                $($P::init_access(&mut access);)*
+            }
+
             fn run(&mut self, main: &mut World, worlds: &mut Worlds) {
+                let ctx = SystemContext::new(main, worlds);
+                // Values and types live in different namespaces, so the states can be bound
+                // to the names of their params.
+                #[allow(non_snake_case)]
+                let ($($P,)*) = &mut self.state;
+                // SAFETY: the accesses of the params were checked against each other in
+                // `init_access`, and the params are dropped before the worlds are borrowed
+                // again in `apply`.
                 unsafe {
-                    let ctx = SystemContext {
-                        main,
-                        worlds,
-                    };
-
-                    (self.func)($($P::get_param($P::init_state(main), &ctx)),*);
+                    if !(true $(&& $P::validate_param(&ctx))*) {
+                        return;
+                    }
+                    $($P::update_state($P, &ctx);)*
+                    (self.func)($($P::get_param($P, &ctx)),*);
+                    $($P::apply($P, &ctx);)*
                 }
             }
         }
@@ -382,11 +654,12 @@ macro_rules! impl_into_world_system {
             $($P: WorldSystemParam + Send + Sync + 'static ,)*
             F: FnMut($($P),*) + Send + Sync + 'static,
         {
-            type System = FunctionSystem<($($P,)*), F>;
+            type System = FunctionSystem<($($P,)*), F, ($($P::State,)*)>;
 
             fn into_system(self) -> Self::System {
                 FunctionSystem {
                     func: self,
+                    state: ($($P::State::default(),)*),
                     _marker: core::marker::PhantomData::<($($P,)*)>,
                 }
             }
@@ -419,6 +692,13 @@ impl_into_world_system!(
 );
 
 pub trait WorldSyncSystem: Send + Sync + 'static {
+    /// Checks the accesses of the params against each other, registering what they access in
+    /// `ids`, see [`SyncAccess`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if two params conflict.
+    fn init_access(&self, ids: &mut World);
     fn run(&mut self, main: &mut World, worlds: &mut Worlds);
 }
 
@@ -429,42 +709,124 @@ pub trait IntoWorldSyncSystem<M> {
 
 #[derive(Resource, Default)]
 pub struct SyncSystems {
-    inner: Vec<Box<dyn WorldSyncSystem>>,
+    inner: Vec<SyncSystemNode>,
+    /// The run order of each point, sorted again when a system is added.
+    order: Option<Map<SyncPoint, Vec<usize>>>,
+    /// The components and resources the systems access, see [`SyncAccess`].
+    ids: World,
 }
 
 impl SyncSystems {
-    pub fn add_sync_system<M>(&mut self, system: impl IntoWorldSyncSystem<M>) {
-        self.inner.push(Box::new(system.into_system()));
+    /// # Panics
+    ///
+    /// Panics if params of the system conflict, e.g. a [`WorldRes`] and a [`WorldResMut`] of
+    /// the same resource in the same world.
+    pub fn add_sync_system<M>(&mut self, system: impl IntoSyncSystemConfig<M>) {
+        let config = system.into_config();
+        config.system.init_access(&mut self.ids);
+        self.inner.push(SyncSystemNode::new(config));
+        self.order = None;
     }
 
+    /// Runs the systems at `point` in order.
+    ///
+    /// [`Worlds`] is taken out of the main world while a system runs, its run conditions are
+    /// evaluated before that and can read it.
+    ///
     /// # Panics
     ///
-    /// Panics if the `Worlds` resource is not found.
-    pub fn run(&mut self, main: &mut World) {
-        let mut worlds = main
-            .remove_resource::<Worlds>()
-            .expect("Worlds resource not found");
-
-        for system in &mut self.inner {
-            system.run(main, &mut worlds);
+    /// Panics if the `Worlds` resource is not found, or if the systems have a cycle in their
+    /// ordering.
+    pub fn run(&mut self, main: &mut World, point: SyncPoint) {
+        let nodes = &self.inner;
+        let order = self.order.get_or_insert_with(|| {
+            [SyncPoint::BeforeTick, SyncPoint::AfterTick]
+                .into_iter()
+                .map(|point| {
+                    let indices: Vec<_> = (0..nodes.len())
+                        .filter(|&index| nodes[index].point() == point)
+                        .collect();
+                    (point, schedule::sort(nodes, &indices))
+                })
+                .collect()
+        });
+        for &index in &order[&point] {
+            let node = &mut self.inner[index];
+            if node.should_run(main) {
+                main.resource_scope(|main, mut worlds: Mut<Worlds>| node.run(main, &mut worlds));
+            }
         }
-        main.insert_resource(worlds);
     }
 }
 
+/// Runs the sync systems at [`SyncPoint::AfterTick`].
+///
 /// # Panics
 ///
 /// Panics if the `SyncSystems` resource is not found.
 pub fn sync_worlds(world: &mut World) {
+    run_sync_systems(world, SyncPoint::AfterTick);
+}
+
+/// Runs the sync systems at [`SyncPoint::BeforeTick`].
+///
+/// # Panics
+///
+/// Panics if the `SyncSystems` resource is not found.
+pub fn sync_worlds_before_tick(world: &mut World) {
+    run_sync_systems(world, SyncPoint::BeforeTick);
+}
+
+fn run_sync_systems(world: &mut World, point: SyncPoint) {
     let mut systems = world
         .remove_resource::<SyncSystems>()
         .expect("SyncSystems resource not found");
-    systems.run(world);
+    systems.run(world, point);
     world.insert_resource(systems);
 }
 
 pub trait MultiworldApp {
-    fn add_sync_system<M>(&mut self, system: impl IntoWorldSyncSystem<M>) -> &mut Self;
+    fn add_sync_system<M>(&mut self, system: impl IntoSyncSystemConfig<M>) -> &mut Self;
+
+    /// Mirrors `S` of the entities of world `W` into `V` on the main world entities with a
+    /// [`SyncTarget`] to them and a [`SyncWorld`] of `W`, after each tick.
+    ///
+    /// `V` is inserted when missing, and only written when `S` or the [`SyncTarget`] changed, so
+    /// `Changed<V>` stays meaningful in the main world.
+    fn sync_component<S, V, W>(
+        &mut self,
+        convert: impl Fn(&S) -> V + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        S: Component,
+        V: Component<Mutability = Mutable>,
+        W: WorldLabel + Default;
+
+    /// Mirrors the resource `S` of world `W` into the main world resource `M` after each tick.
+    ///
+    /// `M` is inserted when missing, and only written when `S` changed.
+    fn sync_resource<S, M, W>(
+        &mut self,
+        convert: impl Fn(&S) -> M + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        S: Resource,
+        M: Resource,
+        W: WorldLabel + Default;
+
+    /// Spawns a visual, built by `visual`, with a [`SyncTarget`] for each entity of world `W`
+    /// that gets the marker `M`, e.g. for entities spawned by the simulation itself.
+    ///
+    /// The visuals are spawned after the sync systems, so they are synced from the next
+    /// update on.
+    fn spawn_visuals<M, B, W>(
+        &mut self,
+        visual: impl Fn(&M) -> B + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        M: Component,
+        B: Bundle,
+        W: WorldLabel + Default;
 
     fn add_world_systems<W: WorldLabel, M>(
         &mut self,
@@ -479,18 +841,63 @@ pub trait MultiworldApp {
         observer: impl IntoObserverSystem<E, B, M>,
     ) -> &mut Self;
 
+    /// Adds a world without input, see [`MultiworldApp::add_world_with_input`].
     fn add_world(&mut self, label: impl WorldLabel) -> &mut Self;
+    /// Adds a world ticked with inputs of type `I`. Worlds with different input types can
+    /// coexist in [`Worlds`].
+    fn add_world_with_input<I: UserInput>(&mut self, label: impl WorldLabel) -> &mut Self;
     fn get_world(&self, label: impl WorldLabel) -> Option<&Subworld>;
     fn modify_world(&mut self, label: impl WorldLabel, f: impl FnOnce(&mut Subworld)) -> &mut Self;
+    fn modify_world_with_input<I: UserInput>(
+        &mut self,
+        label: impl WorldLabel,
+        f: impl FnOnce(&mut Subworld<I>),
+    ) -> &mut Self;
 }
 
 impl MultiworldApp for App {
-    fn add_sync_system<M>(&mut self, system: impl IntoWorldSyncSystem<M>) -> &mut Self {
+    fn add_sync_system<M>(&mut self, system: impl IntoSyncSystemConfig<M>) -> &mut Self {
         let mut resource = self.world_mut().resource_mut::<SyncSystems>();
         resource.add_sync_system(system);
         self
     }
 
+    fn sync_component<S, V, W>(
+        &mut self,
+        convert: impl Fn(&S) -> V + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        S: Component,
+        V: Component<Mutability = Mutable>,
+        W: WorldLabel + Default,
+    {
+        self.add_sync_system(mirror::component::<S, V, W>(convert))
+    }
+
+    fn sync_resource<S, M, W>(
+        &mut self,
+        convert: impl Fn(&S) -> M + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        S: Resource,
+        M: Resource,
+        W: WorldLabel + Default,
+    {
+        self.add_sync_system(mirror::resource::<S, M, W>(convert))
+    }
+
+    fn spawn_visuals<M, B, W>(
+        &mut self,
+        visual: impl Fn(&M) -> B + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        M: Component,
+        B: Bundle,
+        W: WorldLabel + Default,
+    {
+        self.add_sync_system(lifecycle::spawn_visuals::<M, B, W>(visual))
+    }
+
     fn add_world_systems<W: WorldLabel, M>(
         &mut self,
         label: W,
@@ -501,8 +908,10 @@ impl MultiworldApp for App {
             self.add_systems(schedule, systems);
         } else {
             let mut resource = self.world_mut().resource_mut::<Worlds>();
-            let world = resource.get_mut(label).unwrap();
-            world.add_systems(schedule, systems);
+            let entry = resource.inner.get_mut(&label.intern()).unwrap();
+            entry
+                .world
+                .add_schedule_configs(schedule.intern(), systems.into_configs());
         }
         self
     }
@@ -516,15 +925,22 @@ impl MultiworldApp for App {
             self.add_observer(observer);
         } else {
             let mut resource = self.world_mut().resource_mut::<Worlds>();
-            let world = resource.get_mut(label).unwrap();
+            let world = resource.world_mut(label).unwrap();
             world.add_observer(observer);
         }
         self
     }
 
     fn add_world(&mut self, label: impl WorldLabel) -> &mut Self {
+        self.add_world_with_input::<()>(label)
+    }
+
+    fn add_world_with_input<I: UserInput>(&mut self, label: impl WorldLabel) -> &mut Self {
+        let world = new_world::<I>(self.world().get_resource::<AppTypeRegistry>());
         let mut resource = self.world_mut().resource_mut::<Worlds>();
-        resource.inner.insert(label.intern(), Subworld::default());
+        resource
+            .inner
+            .insert(label.intern(), WorldEntry::new(Box::new(world)));
         self
     }
 
@@ -535,8 +951,16 @@ impl MultiworldApp for App {
     }
 
     fn modify_world(&mut self, label: impl WorldLabel, f: impl FnOnce(&mut Subworld)) -> &mut Self {
+        self.modify_world_with_input(label, f)
+    }
+
+    fn modify_world_with_input<I: UserInput>(
+        &mut self,
+        label: impl WorldLabel,
+        f: impl FnOnce(&mut Subworld<I>),
+    ) -> &mut Self {
         let mut resource = self.world_mut().resource_mut::<Worlds>();
-        if let Some(world) = resource.get_mut(label) {
+        if let Some(world) = resource.get_with_input_mut(label) {
             f(world);
         } else {
             panic!("World not found");
@@ -549,33 +973,127 @@ impl MultiworldApp for App {
 pub struct MultiworldCommands<'w, 's> {
     pub commands: Commands<'w, 's>,
     worlds: ResMut<'w, Worlds>,
+    registry: Option<Res<'w, AppTypeRegistry>>,
+    synced: Query<'w, 's, (Entity, &'static SyncWorld)>,
 }
 
-impl<'w> MultiworldCommands<'w, '_> {
+impl MultiworldCommands<'_, '_> {
     pub fn spawn_at(
-        &'w mut self,
+        &mut self,
         label: impl WorldLabel,
         target: impl Bundle,
         visual: impl Bundle,
-    ) -> (Entity, EntityCommands<'w>) {
-        let world = self.worlds.get_mut(label).unwrap();
+    ) -> (Entity, EntityCommands<'_>) {
+        let label = label.intern();
+        let world = self.worlds.world_mut(label).unwrap();
         let mut other_commands = world.commands();
         let other = other_commands.spawn(target).id();
         let mut visual = self.commands.spawn(visual);
-        visual.insert(SyncTarget(other));
+        visual.insert((SyncTarget(other), SyncWorld(label)));
         (other, visual)
     }
 
     pub fn spawn_empty_at(&mut self, label: impl WorldLabel, target: impl Bundle) -> Entity {
-        let world = self.worlds.get_mut(label).unwrap();
+        let world = self.worlds.world_mut(label).unwrap();
         let mut other_commands = world.commands();
         other_commands.spawn(target).id()
     }
 
     pub fn world_commands(&mut self, label: impl WorldLabel) -> Commands<'_, '_> {
-        let world = self.worlds.get_mut(label).unwrap();
+        let world = self.worlds.world_mut(label).unwrap();
         world.commands()
     }
+
+    /// Creates a world without input at runtime, e.g. when a match starts, built by `plugin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a world with this label already exists.
+    pub fn add_world(&mut self, label: impl WorldLabel, plugin: impl DPlugin<()>) {
+        self.add_world_with_input(label, plugin);
+    }
+
+    /// Like [`MultiworldCommands::add_world`], for a world ticked with inputs of type `I`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a world with this label already exists.
+    pub fn add_world_with_input<I: UserInput>(
+        &mut self,
+        label: impl WorldLabel,
+        plugin: impl DPlugin<I>,
+    ) {
+        let label = label.intern();
+        assert!(!self.worlds.contains(label), "World already exists");
+        let mut world = new_world::<I>(self.registry.as_deref());
+        world.add_plugin(plugin);
+        self.worlds
+            .inner
+            .insert(label, WorldEntry::new(Box::new(world)));
+    }
+
+    /// Replaces a world by a [`Subworld::rebuild`] of it, so it starts over from its first tick
+    /// with the same plugins, and despawns the main world entities synced to it.
+    ///
+    /// The tick budget of the world is kept, its tick statistics start over.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the world is not found.
+    pub fn reset_world(&mut self, label: impl WorldLabel) {
+        let label = label.intern();
+        let entry = self.worlds.inner.get_mut(&label).expect("World not found");
+        let mut reset = WorldEntry::new(entry.world.rebuild());
+        reset.budget = entry.budget;
+        *entry = reset;
+        self.despawn_synced(label);
+    }
+
+    /// Removes a world, e.g. when a match ends, and despawns the main world entities synced to
+    /// it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the world is not found.
+    pub fn remove_world(&mut self, label: impl WorldLabel) -> Box<dyn AnySubworld> {
+        let label = label.intern();
+        let world = self.worlds.remove(label).expect("World not found");
+        self.despawn_synced(label);
+        world
+    }
+
+    fn despawn_synced(&mut self, label: Interned<dyn WorldLabel>) {
+        for (entity, world) in &self.synced {
+            if world.0 == label {
+                self.commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    /// Queues `system` to run once inside the world, before its next tick.
+    ///
+    /// The system runs later, so its result can't be returned. A failure goes to the error
+    /// handler of the world and a skip, e.g. because a param failed validation, is logged.
+    pub fn run_system_at<M>(
+        &mut self,
+        label: impl WorldLabel,
+        system: impl IntoSystem<(), (), M> + Send + 'static,
+    ) {
+        let label = label.intern();
+        let world = self.worlds.world_mut(label).unwrap();
+        world.commands().queue(
+            move |world: &mut World| match world.run_system_once(system) {
+                Ok(()) => Ok(()),
+                Err(RunSystemError::Skipped(skipped)) => {
+                    warn!(
+                        "System queued with run_system_at in world {label:?} was skipped: {skipped}"
+                    );
+                    Ok(())
+                }
+                Err(RunSystemError::Failed(error)) => Err(error),
+            },
+        );
+    }
 }
 
 pub struct SyncPlugin;
@@ -583,6 +1101,18 @@ impl Plugin for SyncPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SyncSystems>();
         app.init_resource::<Worlds>();
-        app.add_systems(Update, sync_worlds);
+        app.init_resource::<OrphanPolicy>();
+        app.configure_sets(
+            Update,
+            (SyncSet::PreTick, SyncSet::Tick, SyncSet::PostTick).chain(),
+        );
+        app.add_systems(
+            Update,
+            (
+                sync_worlds_before_tick.in_set(SyncSet::PreTick),
+                handle_orphans.in_set(SyncSet::PostTick).before(sync_worlds),
+                sync_worlds.in_set(SyncSet::PostTick),
+            ),
+        );
     }
 }
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::{
+    IntoSyncSystemConfig, SyncSystemConfig, SyncTarget, SyncWorld, WorldCommands, WorldLabel,
+    WorldQuery, Worlds,
+};
+
+/// What happens to a visual, a main world entity with a [`SyncTarget`] and a [`SyncWorld`],
+/// once its target is despawned or its world is removed.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanPolicy {
+    #[default]
+    Despawn,
+    /// Hides the visual and marks it with [`Orphan`].
+    #[cfg(feature = "visibility")]
+    Hide,
+    /// Only marks the visual with [`Orphan`], e.g. to fade it out before despawning it.
+    Keep,
+}
+
+/// A visual whose [`SyncTarget`] is gone, see [`OrphanPolicy`].
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Orphan;
+
+/// Applies the [`OrphanPolicy`] to the visuals whose target is gone.
+///
+/// Runs before [`sync_worlds`](crate::sync_worlds), so the sync systems don't see the
+/// despawned visuals. Targets spawned from the main world exist once they are queued, before
+/// the world ticks and spawns them.
+pub fn handle_orphans(
+    mut commands: Commands,
+    policy: Res<OrphanPolicy>,
+    worlds: Res<Worlds>,
+    visuals: Query<(Entity, &SyncTarget, &SyncWorld), Without<Orphan>>,
+) {
+    for (entity, target, world) in &visuals {
+        let exists = worlds
+            .world(world.0)
+            .is_some_and(|world| world.entities().contains(target.0));
+        if exists {
+            continue;
+        }
+        match *policy {
+            OrphanPolicy::Despawn => {
+                commands.entity(entity).despawn();
+            }
+            #[cfg(feature = "visibility")]
+            OrphanPolicy::Hide => {
+                commands.entity(entity).insert((Orphan, Visibility::Hidden));
+            }
+            OrphanPolicy::Keep => {
+                commands.entity(entity).insert(Orphan);
+            }
+        }
+    }
+}
+
+/// See [`MultiworldApp::spawn_visuals`](crate::MultiworldApp::spawn_visuals).
+pub(crate) fn spawn_visuals<M, B, W>(
+    visual: impl Fn(&M) -> B + Send + Sync + 'static,
+) -> SyncSystemConfig
+where
+    M: Component,
+    B: Bundle,
+    W: WorldLabel + Default,
+{
+    let label = W::default().intern();
+    let spawn = move |sims: WorldQuery<(Entity, &M), Added<M>, W>, mut commands: WorldCommands| {
+        for (entity, marker) in sims.iter() {
+            commands.spawn((visual(marker), SyncTarget(entity), SyncWorld(label)));
+        }
+    };
+    spawn.into_config()
+}
@@ -0,0 +1,69 @@
+use bevy::{ecs::component::Mutable, prelude::*};
+
+use crate::{
+    IntoSyncSystemConfig, SyncSystemConfig, SyncTarget, SyncWorld, WorldCommands, WorldLabel,
+    WorldQuery, WorldRes, WorldResMut,
+};
+
+/// See [`MultiworldApp::sync_component`](crate::MultiworldApp::sync_component).
+pub(crate) fn component<S, V, W>(
+    convert: impl Fn(&S) -> V + Send + Sync + 'static,
+) -> SyncSystemConfig
+where
+    S: Component,
+    V: Component<Mutability = Mutable>,
+    W: WorldLabel + Default,
+{
+    let label = W::default().intern();
+    let mirror =
+        move |from: WorldQuery<Ref<S>, (), W>,
+              mut to: WorldQuery<(Entity, Ref<SyncTarget>, &SyncWorld, Option<&mut V>)>,
+              mut commands: WorldCommands| {
+            for (entity, target, world, visual) in to.iter_mut() {
+                if world.0 != label {
+                    continue;
+                }
+                let Ok(sim) = from.get(target.0) else {
+                    continue;
+                };
+                match visual {
+                    Some(mut visual) => {
+                        if sim.is_changed() || target.is_changed() {
+                            *visual = convert(&sim);
+                        }
+                    }
+                    None => {
+                        commands.entity(entity).insert(convert(&sim));
+                    }
+                }
+            }
+        };
+    mirror.into_config()
+}
+
+/// See [`MultiworldApp::sync_resource`](crate::MultiworldApp::sync_resource).
+pub(crate) fn resource<S, M, W>(
+    convert: impl Fn(&S) -> M + Send + Sync + 'static,
+) -> SyncSystemConfig
+where
+    S: Resource,
+    M: Resource,
+    W: WorldLabel + Default,
+{
+    let mirror = move |from: Option<WorldRes<S, W>>,
+                       to: Option<WorldResMut<M>>,
+                       mut commands: WorldCommands| {
+        let Some(from) = from else {
+            return;
+        };
+        match to {
+            Some(mut to) => {
+                if from.is_changed() {
+                    *to = convert(&from);
+                }
+            }
+            None => commands.insert_resource(convert(&from)),
+        }
+    };
+    mirror.into_config()
+}
@@ -0,0 +1,194 @@
+use alloc::{collections::BTreeSet, vec};
+
+use bevy::{
+    ecs::schedule::{BoxedCondition, InternedSystemSet, SystemCondition},
+    platform::prelude::{Box, Vec},
+    prelude::*,
+};
+
+use crate::{IntoWorldSyncSystem, WorldSyncSystem, Worlds};
+
+/// Where the sync systems run in the main world's `Update`, in this order.
+///
+/// Tick the Subworlds in [`SyncSet::Tick`], so the sync systems at [`SyncPoint::BeforeTick`]
+/// see the state before the ticks and the ones at [`SyncPoint::AfterTick`] see it after.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncSet {
+    PreTick,
+    Tick,
+    PostTick,
+}
+
+/// When a sync system runs relative to the Subworld ticks, see [`SyncSet`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncPoint {
+    /// E.g. to send inputs or main world changes into the Subworlds.
+    BeforeTick,
+    /// E.g. to copy the simulation into visuals.
+    #[default]
+    AfterTick,
+}
+
+/// A sync system with its ordering and run conditions, built from a sync system with the
+/// methods of [`IntoSyncSystemConfig`].
+pub struct SyncSystemConfig {
+    pub(crate) system: Box<dyn WorldSyncSystem>,
+    pub(crate) point: SyncPoint,
+    pub(crate) sets: Vec<InternedSystemSet>,
+    pub(crate) before: Vec<InternedSystemSet>,
+    pub(crate) after: Vec<InternedSystemSet>,
+    pub(crate) conditions: Vec<BoxedCondition>,
+}
+
+#[doc(hidden)]
+pub struct IsSyncFunction;
+
+/// Configures how a sync system is scheduled, like bevy's `IntoScheduleConfigs`.
+///
+/// Sync systems are ordered through the [`SystemSet`]s they are in, and only relative to the
+/// other sync systems at the same [`SyncPoint`]. Unordered systems run in the order they were
+/// added. Regular bevy systems are not part of that order, so `before` and `after` take sets
+/// rather than systems.
+pub trait IntoSyncSystemConfig<M>: Sized {
+    fn into_config(self) -> SyncSystemConfig;
+
+    fn in_set(self, set: impl SystemSet) -> SyncSystemConfig {
+        let mut config = self.into_config();
+        config.sets.push(set.intern());
+        config
+    }
+
+    /// Runs before the sync systems in `set`.
+    fn before(self, set: impl SystemSet) -> SyncSystemConfig {
+        let mut config = self.into_config();
+        config.before.push(set.intern());
+        config
+    }
+
+    /// Runs after the sync systems in `set`.
+    fn after(self, set: impl SystemSet) -> SyncSystemConfig {
+        let mut config = self.into_config();
+        config.after.push(set.intern());
+        config
+    }
+
+    /// Only runs when `condition`, evaluated on the main world right before the system, returns
+    /// `true`.
+    fn run_if<C>(self, condition: impl SystemCondition<C>) -> SyncSystemConfig {
+        let mut config = self.into_config();
+        config
+            .conditions
+            .push(Box::new(IntoSystem::into_system(condition)));
+        config
+    }
+
+    fn at(self, point: SyncPoint) -> SyncSystemConfig {
+        let mut config = self.into_config();
+        config.point = point;
+        config
+    }
+}
+
+impl<M, S: IntoWorldSyncSystem<M>> IntoSyncSystemConfig<(IsSyncFunction, M)> for S {
+    fn into_config(self) -> SyncSystemConfig {
+        SyncSystemConfig {
+            system: Box::new(self.into_system()),
+            point: SyncPoint::default(),
+            sets: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+}
+
+impl IntoSyncSystemConfig<()> for SyncSystemConfig {
+    fn into_config(self) -> SyncSystemConfig {
+        self
+    }
+}
+
+pub(crate) struct SyncSystemNode {
+    config: SyncSystemConfig,
+    conditions_initialized: bool,
+}
+
+impl SyncSystemNode {
+    pub(crate) fn new(config: SyncSystemConfig) -> Self {
+        Self {
+            config,
+            conditions_initialized: false,
+        }
+    }
+
+    pub(crate) const fn point(&self) -> SyncPoint {
+        self.config.point
+    }
+
+    /// If `self` has to run before `other`.
+    pub(crate) fn runs_before(&self, other: &Self) -> bool {
+        let (this, other) = (&self.config, &other.config);
+        this.before.iter().any(|set| other.sets.contains(set))
+            || other.after.iter().any(|set| this.sets.contains(set))
+    }
+
+    /// Evaluates the run conditions, on the whole main world, [`Worlds`] included.
+    pub(crate) fn should_run(&mut self, main: &mut World) -> bool {
+        if !self.conditions_initialized {
+            for condition in &mut self.config.conditions {
+                condition.initialize(main);
+            }
+            self.conditions_initialized = true;
+        }
+        // Like bevy, a condition whose params are missing counts as false.
+        let mut should_run = true;
+        for condition in &mut self.config.conditions {
+            should_run &= condition.run((), main).unwrap_or(false);
+        }
+        should_run
+    }
+
+    pub(crate) fn run(&mut self, main: &mut World, worlds: &mut Worlds) {
+        self.config.system.run(main, worlds);
+    }
+}
+
+/// Orders `nodes` by their constraints with Kahn's algorithm. Of the systems ready to run, the
+/// one added first goes first, so the insertion order is kept where there are no constraints.
+///
+/// # Panics
+///
+/// Panics if the constraints form a cycle.
+pub(crate) fn sort(nodes: &[SyncSystemNode], indices: &[usize]) -> Vec<usize> {
+    // Edges between positions in `indices`.
+    let mut successors = vec![Vec::new(); indices.len()];
+    let mut predecessors = vec![0_usize; indices.len()];
+    for (from, &first) in indices.iter().enumerate() {
+        for (to, &second) in indices.iter().enumerate() {
+            if from != to && nodes[first].runs_before(&nodes[second]) {
+                successors[from].push(to);
+                predecessors[to] += 1;
+            }
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..indices.len())
+        .filter(|&position| predecessors[position] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(indices.len());
+    while let Some(position) = ready.pop_first() {
+        order.push(indices[position]);
+        for &next in &successors[position] {
+            predecessors[next] -= 1;
+            if predecessors[next] == 0 {
+                ready.insert(next);
+            }
+        }
+    }
+    assert_eq!(
+        order.len(),
+        indices.len(),
+        "Sync systems have a cycle in their before/after constraints"
+    );
+    order
+}
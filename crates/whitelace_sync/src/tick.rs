@@ -0,0 +1,214 @@
+use core::time::Duration;
+
+#[allow(clippy::disallowed_types)]
+use bevy::platform::time::Instant;
+use bevy::{
+    ecs::intern::Interned,
+    platform::prelude::{Box, Vec},
+    tasks::{ComputeTaskPool, TaskPool},
+};
+use whitelace_core::{
+    main::{
+        input::UserInput,
+        schedule::{SimTick, SubworldExecutor},
+    },
+    map::Set,
+};
+
+use crate::{AnySubworld, WorldLabel, Worlds, downcast_mut};
+
+/// Limits how many ticks a world runs per [`Worlds::tick_all`], [`Worlds::tick_worlds`] or
+/// [`Worlds::tick_worlds_with_input`] call. Ticks over the budget stay pending and run in later calls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickBudget {
+    /// Most ticks run per call, `0` pauses the world.
+    pub max_ticks: u32,
+    /// Stops ticking once a call took this long. At least one tick is run, so a slow world
+    /// still makes progress.
+    ///
+    /// This only decides when ticks run, never what they compute, but it makes the number of
+    /// ticks per call depend on the machine.
+    pub max_time: Option<Duration>,
+}
+
+impl Default for TickBudget {
+    fn default() -> Self {
+        Self {
+            max_ticks: 8,
+            max_time: None,
+        }
+    }
+}
+
+/// Tick statistics of a world in [`Worlds`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickStats {
+    /// Ticks requested but not run yet because of the [`TickBudget`].
+    pub pending_ticks: u32,
+    /// Ticks run in the last call.
+    pub last_ticks: u32,
+    pub last_duration: Duration,
+    pub total_ticks: u64,
+    pub total_duration: Duration,
+    /// The longest single tick so far.
+    pub max_tick_duration: Duration,
+}
+
+pub(crate) struct WorldEntry {
+    pub(crate) world: Box<dyn AnySubworld>,
+    pub(crate) budget: TickBudget,
+    pub(crate) stats: TickStats,
+}
+
+impl WorldEntry {
+    pub(crate) fn new(world: Box<dyn AnySubworld>) -> Self {
+        Self {
+            world,
+            budget: TickBudget::default(),
+            stats: TickStats::default(),
+        }
+    }
+
+    // Wall clock time only limits how many ticks run, the simulation itself never sees it.
+    #[allow(clippy::disallowed_types)]
+    fn run(
+        &mut self,
+        label: Interned<dyn WorldLabel>,
+        tick: &impl Fn(Interned<dyn WorldLabel>, &mut dyn AnySubworld),
+    ) {
+        let start = Instant::now();
+        let ticks = self.stats.pending_ticks.min(self.budget.max_ticks);
+        let mut ran = 0;
+        while ran < ticks {
+            if ran > 0
+                && self
+                    .budget
+                    .max_time
+                    .is_some_and(|max| start.elapsed() >= max)
+            {
+                break;
+            }
+            let tick_start = Instant::now();
+            tick(label, self.world.as_mut());
+            self.stats.max_tick_duration = self.stats.max_tick_duration.max(tick_start.elapsed());
+            ran += 1;
+        }
+
+        let duration = start.elapsed();
+        self.stats.pending_ticks -= ran;
+        self.stats.last_ticks = ran;
+        self.stats.last_duration = duration;
+        self.stats.total_ticks += u64::from(ran);
+        self.stats.total_duration += duration;
+    }
+}
+
+impl Worlds {
+    /// Requests `ticks` more ticks of every world and runs them in parallel, each world within
+    /// its [`TickBudget`]. The ticks run without input, see [`Worlds::tick_worlds_with_input`]
+    /// for worlds driven by input.
+    ///
+    /// Worlds are independent, so the result doesn't depend on which thread ticks which world.
+    /// The ticks run on the [`ComputeTaskPool`], which is only multi-threaded with the
+    /// `multi_threaded` feature. Worlds using [`SubworldExecutor::MultiThreaded`] already run
+    /// their systems on that pool, so they are ticked one after the other on the calling thread
+    /// instead of from within the pool.
+    pub fn tick_all(&mut self, ticks: u32) {
+        self.tick_filtered(ticks, |_| true, |_, world| world.tick_without_input());
+    }
+
+    /// Like [`Worlds::tick_all`], but only for the worlds in `labels`.
+    pub fn tick_worlds<W: WorldLabel>(&mut self, labels: impl IntoIterator<Item = W>, ticks: u32) {
+        let labels: Set<_> = labels.into_iter().map(|label| label.intern()).collect();
+        self.tick_filtered(
+            ticks,
+            |label| labels.contains(label),
+            |_, world| world.tick_without_input(),
+        );
+    }
+
+    /// Like [`Worlds::tick_worlds`], for worlds with the input type `I`. The input of each tick
+    /// is returned by `input`, called with the label of the world and the tick about to run,
+    /// possibly from several threads at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the worlds has another input type than `I`.
+    pub fn tick_worlds_with_input<W: WorldLabel, I: UserInput>(
+        &mut self,
+        labels: impl IntoIterator<Item = W>,
+        ticks: u32,
+        input: impl Fn(Interned<dyn WorldLabel>, SimTick) -> Vec<I> + Sync,
+    ) {
+        let labels: Set<_> = labels.into_iter().map(|label| label.intern()).collect();
+        // Check the input types before any world ticks.
+        for label in &labels {
+            if let Some(entry) = self.inner.get_mut(label) {
+                downcast_mut::<I>(entry.world.as_mut(), *label);
+            }
+        }
+        self.tick_filtered(
+            ticks,
+            |label| labels.contains(label),
+            |label, world| {
+                let world = downcast_mut::<I>(world, label);
+                let tick = *world.resource::<SimTick>();
+                world.tick(input(label, tick));
+            },
+        );
+    }
+
+    fn tick_filtered(
+        &mut self,
+        ticks: u32,
+        filter: impl Fn(&Interned<dyn WorldLabel>) -> bool,
+        tick: impl Fn(Interned<dyn WorldLabel>, &mut dyn AnySubworld) + Sync,
+    ) {
+        let tick = &tick;
+        let mut sequential = Vec::new();
+        let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        pool.scope(|scope| {
+            for (label, entry) in &mut self.inner {
+                if !filter(label) {
+                    continue;
+                }
+                entry.stats.pending_ticks = entry.stats.pending_ticks.saturating_add(ticks);
+                // Ticking from within a task would nest its scopes in the ones of this call.
+                if entry.world.executor() == SubworldExecutor::SingleThreaded {
+                    scope.spawn(async move { entry.run(*label, tick) });
+                } else {
+                    sequential.push((*label, entry));
+                }
+            }
+        });
+        for (label, entry) in sequential {
+            entry.run(label, tick);
+        }
+    }
+
+    pub fn tick_budget(&self, label: impl WorldLabel) -> Option<TickBudget> {
+        self.inner.get(&label.intern()).map(|entry| entry.budget)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there is no world with this label.
+    pub fn set_tick_budget(&mut self, label: impl WorldLabel, budget: TickBudget) {
+        self.inner
+            .get_mut(&label.intern())
+            .expect("World not found")
+            .budget = budget;
+    }
+
+    pub fn tick_stats(&self, label: impl WorldLabel) -> Option<&TickStats> {
+        self.inner.get(&label.intern()).map(|entry| &entry.stats)
+    }
+
+    /// Drops the pending ticks of a world, e.g. to let it catch up after a stall instead of
+    /// running the backlog.
+    pub fn clear_pending_ticks(&mut self, label: impl WorldLabel) {
+        if let Some(entry) = self.inner.get_mut(&label.intern()) {
+            entry.stats.pending_ticks = 0;
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use whitelace_sync::{
+    MultiworldApp, SyncPlugin, WorldCommands, WorldLabel, WorldQuery, WorldRes, WorldResMut, Worlds,
+};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+#[derive(Resource, Default)]
+struct Score(u32);
+
+#[derive(Component)]
+struct Health(u32);
+
+#[derive(Component)]
+struct Boss;
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .modify_world(Sim, |world| {
+            world.insert_resource(Score(3));
+            world.spawn(Health(10));
+            world.spawn((Health(100), Boss));
+        });
+    app.insert_resource(Score(0));
+    app
+}
+
+#[test]
+#[should_panic = "conflicts with a previous WorldResMut"]
+fn resource_read_after_write_conflicts() {
+    app().add_sync_system(|_: WorldResMut<Score, Sim>, _: WorldRes<Score, Sim>| {});
+}
+
+#[test]
+#[should_panic = "conflicts with a previous access"]
+fn resource_write_after_read_conflicts() {
+    app().add_sync_system(|_: WorldRes<Score>, _: WorldResMut<Score>| {});
+}
+
+#[test]
+#[should_panic = "Consider `Without<T>` filters"]
+fn overlapping_queries_conflict() {
+    app()
+        .add_sync_system(|_: WorldQuery<&mut Health, (), Sim>, _: WorldQuery<&Health, (), Sim>| {});
+}
+
+#[test]
+fn disjoint_accesses_run() {
+    let mut app = app();
+    app.add_sync_system(
+        |sim: WorldRes<Score, Sim>,
+         mut main: WorldResMut<Score>,
+         mut minions: WorldQuery<&mut Health, Without<Boss>, Sim>,
+         bosses: WorldQuery<&Health, With<Boss>, Sim>| {
+            main.0 = sim.0;
+            let boss = bosses.iter().next().unwrap().0;
+            for mut health in minions.iter_mut() {
+                health.0 += boss;
+            }
+        },
+    );
+    app.update();
+
+    assert_eq!(app.world().resource::<Score>().0, 3);
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    let world = worlds.world_mut(Sim).unwrap();
+    let mut healths: Vec<_> = world
+        .query::<&Health>()
+        .iter(world)
+        .map(|health| health.0)
+        .collect();
+    healths.sort_unstable();
+    assert_eq!(healths, [100, 110]);
+}
+
+#[test]
+fn commands_reach_their_world() {
+    let mut app = app();
+    app.add_sync_system(
+        |mut sim: WorldCommands<Sim>, mut main: WorldCommands, _: WorldResMut<Score, Sim>| {
+            sim.insert_resource(Score(7));
+            main.spawn(Boss);
+        },
+    );
+    app.update();
+
+    let boss = app
+        .world_mut()
+        .query_filtered::<(), With<Boss>>()
+        .iter(app.world())
+        .count();
+    assert_eq!(boss, 1);
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    let world = worlds.world_mut(Sim).unwrap();
+    world.flush();
+    assert_eq!(world.resource::<Score>().0, 7);
+}
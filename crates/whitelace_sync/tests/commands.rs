@@ -0,0 +1,173 @@
+use bevy::{
+    ecs::{intern::Interned, system::RunSystemOnce},
+    prelude::*,
+};
+use whitelace_core::main::{
+    DPlugin, Subworld,
+    schedule::{FixedUpdate, SimTick, Startup},
+};
+use whitelace_sync::{
+    MultiworldApp, MultiworldCommands, SyncPlugin, SyncTarget, SyncWorld, WorldLabel, Worlds,
+};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Arena;
+
+#[derive(Resource, Default)]
+struct Score(u32);
+
+#[derive(Component)]
+struct Boss;
+
+#[derive(Component)]
+struct Unit;
+
+/// Spawns a boss on startup and scores every tick.
+struct Level;
+
+impl DPlugin<()> for Level {
+    fn build(&self, world: &mut Subworld) {
+        world.init_resource::<Score>();
+        world.add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(Boss);
+        });
+        world.add_systems(FixedUpdate, |mut score: ResMut<Score>| score.0 += 1);
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .modify_world(Sim, |world| {
+            world.init_resource::<Score>();
+        });
+    app
+}
+
+fn run<T: 'static>(
+    app: &mut App,
+    f: impl Fn(&mut MultiworldCommands) -> T + Send + Sync + 'static,
+) -> T {
+    app.world_mut()
+        .run_system_once(move |mut commands: MultiworldCommands| f(&mut commands))
+        .unwrap()
+}
+
+/// Adds the arena with a unit and its visual, and a unit of the sim world with its visual.
+fn arena_app() -> (App, Entity, Entity) {
+    let mut app = app();
+    let (unit, visual) = run(&mut app, |commands| {
+        commands.add_world(Arena, Level);
+        commands.spawn_at(Sim, Unit, ());
+        let (unit, visual) = commands.spawn_at(Arena, Unit, ());
+        (unit, visual.id())
+    });
+    app.world_mut().resource_mut::<Worlds>().tick_all(2);
+    (app, unit, visual)
+}
+
+fn count<C: Component>(app: &mut App, label: impl WorldLabel) -> usize {
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    let world = worlds.world_mut(label).unwrap();
+    world.query_filtered::<(), With<C>>().iter(world).count()
+}
+
+fn visuals(app: &mut App) -> Vec<Interned<dyn WorldLabel>> {
+    app.world_mut()
+        .query::<&SyncWorld>()
+        .iter(app.world())
+        .map(|world| world.0)
+        .collect()
+}
+
+fn score(app: &App) -> u32 {
+    let worlds = app.world().resource::<Worlds>();
+    worlds.get(Sim).unwrap().resource::<Score>().0
+}
+
+#[test]
+fn systems_run_before_the_next_tick() {
+    let mut app = app();
+    run(&mut app, |commands| {
+        commands.run_system_at(Sim, |mut score: ResMut<Score>| score.0 = 5);
+    });
+    assert_eq!(score(&app), 0);
+
+    app.world_mut().resource_mut::<Worlds>().tick_all(1);
+    assert_eq!(score(&app), 5);
+}
+
+#[test]
+fn skipped_systems_are_not_errors() {
+    let mut app = app();
+    run(&mut app, |commands| {
+        commands.run_system_at(Sim, |_: Single<&Boss>, mut score: ResMut<Score>| {
+            score.0 = 5;
+        });
+    });
+
+    app.world_mut().resource_mut::<Worlds>().tick_all(1);
+    assert_eq!(score(&app), 0);
+}
+
+#[test]
+fn worlds_added_at_runtime_tick() {
+    let (mut app, unit, visual) = arena_app();
+    let worlds = app.world().resource::<Worlds>();
+    let arena = worlds.get(Arena).unwrap();
+    assert_eq!(arena.resource::<Score>().0, 2);
+    assert_eq!(arena.resource::<SimTick>().get(), 2);
+    assert!(arena.get::<Unit>(unit).is_some());
+    assert_eq!(count::<Boss>(&mut app, Arena), 1);
+    assert_eq!(app.world().get::<SyncTarget>(visual).unwrap().0, unit);
+}
+
+#[test]
+#[should_panic = "World already exists"]
+fn worlds_are_added_once() {
+    let mut app = app();
+    run(&mut app, |commands| commands.add_world(Sim, Level));
+}
+
+#[test]
+fn reset_worlds_start_over() {
+    let (mut app, _, visual) = arena_app();
+    run(&mut app, |commands| commands.reset_world(Arena));
+
+    let worlds = app.world().resource::<Worlds>();
+    let arena = worlds.get(Arena).unwrap();
+    assert_eq!(arena.resource::<Score>().0, 0);
+    assert_eq!(arena.resource::<SimTick>().get(), 0);
+    // Both the sim entity and its visual are gone, the startup state comes back on the first
+    // tick.
+    assert_eq!(count::<Unit>(&mut app, Arena), 0);
+    assert!(app.world().get_entity(visual).is_err());
+    assert_eq!(visuals(&mut app), [Sim.intern()]);
+
+    app.world_mut().resource_mut::<Worlds>().tick_all(1);
+    assert_eq!(count::<Boss>(&mut app, Arena), 1);
+    let worlds = app.world().resource::<Worlds>();
+    assert_eq!(worlds.get(Arena).unwrap().resource::<Score>().0, 1);
+}
+
+#[test]
+fn removed_worlds_take_their_visuals() {
+    let (mut app, unit, visual) = arena_app();
+    let removed = run(&mut app, |commands| commands.remove_world(Arena));
+
+    assert!(removed.world().get::<Unit>(unit).is_some());
+    assert!(!app.world().resource::<Worlds>().contains(Arena));
+    assert!(app.world().get_entity(visual).is_err());
+    assert_eq!(visuals(&mut app), [Sim.intern()]);
+
+    // The other worlds keep running.
+    app.update();
+    app.world_mut().resource_mut::<Worlds>().tick_all(1);
+    let worlds = app.world().resource::<Worlds>();
+    assert_eq!(worlds.get(Sim).unwrap().resource::<SimTick>().get(), 3);
+    assert_eq!(count::<Unit>(&mut app, Sim), 1);
+}
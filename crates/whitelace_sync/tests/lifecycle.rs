@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use whitelace_sync::{
+    MultiworldApp, Orphan, OrphanPolicy, SyncPlugin, SyncTarget, SyncWorld, WorldLabel, Worlds,
+};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Other;
+
+#[derive(Component)]
+struct Unit;
+
+/// A unit in both worlds, with the same id, and a visual of each.
+fn app() -> (App, Entity, [Entity; 2]) {
+    let mut app = App::new();
+    let mut unit = Entity::PLACEHOLDER;
+    let mut other = Entity::PLACEHOLDER;
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .add_world(Other)
+        .modify_world(Sim, |world| {
+            unit = world.spawn(Unit).id();
+        })
+        .modify_world(Other, |world| {
+            other = world.spawn(Unit).id();
+        });
+    assert_eq!(unit, other);
+    let visuals = [Sim.intern(), Other.intern()].map(|world| {
+        app.world_mut()
+            .spawn((SyncTarget(unit), SyncWorld(world)))
+            .id()
+    });
+    (app, unit, visuals)
+}
+
+fn despawn_unit(app: &mut App, unit: Entity) {
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    worlds.get_mut(Sim).unwrap().despawn(unit);
+}
+
+#[test]
+fn orphans_are_despawned_by_their_world() {
+    let (mut app, unit, [sim, other]) = app();
+    app.update();
+    assert!(app.world().get_entity(sim).is_ok());
+
+    despawn_unit(&mut app, unit);
+    app.update();
+    assert!(app.world().get_entity(sim).is_err());
+    assert!(app.world().get_entity(other).is_ok());
+}
+
+#[test]
+fn orphans_are_marked_when_kept() {
+    let (mut app, unit, [sim, other]) = app();
+    app.insert_resource(OrphanPolicy::Keep);
+    despawn_unit(&mut app, unit);
+    app.update();
+    assert!(app.world().get::<Orphan>(sim).is_some());
+    assert!(app.world().get::<Orphan>(other).is_none());
+}
+
+#[test]
+#[should_panic = "without a SyncWorld"]
+fn targets_need_their_world() {
+    let (mut app, unit, _) = app();
+    app.world_mut().spawn(SyncTarget(unit));
+}
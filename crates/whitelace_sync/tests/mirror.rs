@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use whitelace_sync::{MultiworldApp, SyncPlugin, SyncTarget, SyncWorld, WorldLabel, Worlds};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+#[derive(Component)]
+struct Health(u32);
+
+#[derive(Component, Debug, PartialEq, Eq)]
+struct Bar(u32);
+
+#[derive(Resource)]
+struct Score(u32);
+
+#[derive(Resource, Debug, PartialEq, Eq)]
+struct Hud(u32);
+
+/// Two units, and a visual of the first one.
+fn app() -> (App, [Entity; 2], Entity) {
+    let mut app = App::new();
+    let mut units = [Entity::PLACEHOLDER; 2];
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .modify_world(Sim, |world| {
+            units = [world.spawn(Health(10)).id(), world.spawn(Health(20)).id()];
+            world.insert_resource(Score(1));
+        })
+        .sync_component::<Health, Bar, Sim>(|health| Bar(health.0))
+        .sync_resource::<Score, Hud, Sim>(|score| Hud(score.0));
+    let visual = app
+        .world_mut()
+        .spawn((SyncTarget(units[0]), SyncWorld(Sim.intern())))
+        .id();
+    (app, units, visual)
+}
+
+fn sim(app: &mut App) -> &mut World {
+    app.world_mut()
+        .resource_mut::<Worlds>()
+        .into_inner()
+        .world_mut(Sim)
+        .unwrap()
+}
+
+fn bar(app: &App, visual: Entity) -> &Bar {
+    app.world().get::<Bar>(visual).unwrap()
+}
+
+#[test]
+fn unchanged_components_keep_the_visual() {
+    let (mut app, [unit, _], visual) = app();
+    app.update();
+    assert_eq!(*bar(&app, visual), Bar(10));
+
+    // Set by the main world, e.g. an animation, and left alone while the sim doesn't change.
+    app.world_mut().get_mut::<Bar>(visual).unwrap().0 = 99;
+    app.update();
+    assert_eq!(*bar(&app, visual), Bar(99));
+
+    sim(&mut app).get_mut::<Health>(unit).unwrap().0 = 5;
+    app.update();
+    assert_eq!(*bar(&app, visual), Bar(5));
+}
+
+#[test]
+fn new_targets_convert_again() {
+    let (mut app, [_, other], visual) = app();
+    app.update();
+    app.update();
+    assert_eq!(*bar(&app, visual), Bar(10));
+
+    app.world_mut().entity_mut(visual).insert(SyncTarget(other));
+    app.update();
+    assert_eq!(*bar(&app, visual), Bar(20));
+}
+
+#[test]
+fn missing_resources_are_inserted() {
+    let (mut app, ..) = app();
+    assert!(app.world().get_resource::<Hud>().is_none());
+    app.update();
+    assert_eq!(*app.world().resource::<Hud>(), Hud(1));
+
+    app.world_mut().resource_mut::<Hud>().0 = 99;
+    app.update();
+    assert_eq!(*app.world().resource::<Hud>(), Hud(99));
+
+    sim(&mut app).resource_mut::<Score>().0 = 2;
+    app.update();
+    assert_eq!(*app.world().resource::<Hud>(), Hud(2));
+}
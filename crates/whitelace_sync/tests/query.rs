@@ -0,0 +1,123 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use whitelace_core::main::{DPlugin, Subworld, schedule::Startup};
+use whitelace_sync::{
+    MultiworldApp, MultiworldCommands, SyncPlugin, WorldLabel, WorldQuery, WorldResMut, Worlds,
+};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+#[derive(Component)]
+struct Health(u32);
+
+/// Spawns two units on startup.
+struct Level;
+
+impl DPlugin<()> for Level {
+    fn build(&self, world: &mut Subworld) {
+        world.add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_batch([Health(10), Health(20)]);
+        });
+    }
+}
+
+/// What each run saw: added, changed and all units.
+#[derive(Resource, Default)]
+struct Seen(Vec<(usize, usize, usize)>);
+
+fn observe(
+    added: WorldQuery<(), Added<Health>, Sim>,
+    changed: WorldQuery<(), Changed<Health>, Sim>,
+    all: WorldQuery<&Health, (), Sim>,
+    mut seen: WorldResMut<Seen>,
+) {
+    seen.0.push((
+        added.iter().count(),
+        changed.iter().count(),
+        all.iter().count(),
+    ));
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .modify_world(Sim, |world| {
+            world.add_plugin(Level);
+        })
+        .init_resource::<Seen>()
+        .add_sync_system(observe);
+    app
+}
+
+fn tick(app: &mut App) {
+    app.world_mut().resource_mut::<Worlds>().tick_all(1);
+    app.update();
+}
+
+fn run(app: &mut App, f: impl Fn(&mut MultiworldCommands) + Send + Sync + 'static) {
+    app.world_mut()
+        .run_system_once(move |mut commands: MultiworldCommands| f(&mut commands))
+        .unwrap();
+}
+
+fn last_seen(app: &App) -> (usize, usize, usize) {
+    *app.world().resource::<Seen>().0.last().unwrap()
+}
+
+#[test]
+fn change_filters_see_each_change_once() {
+    let mut app = app();
+    tick(&mut app);
+    assert_eq!(last_seen(&app), (2, 2, 2));
+    app.update();
+    assert_eq!(last_seen(&app), (0, 0, 2));
+
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    let world = worlds.world_mut(Sim).unwrap();
+    let mut healths = world.query::<&mut Health>();
+    healths.iter_mut(world).next().unwrap().0 += 1;
+    app.update();
+    assert_eq!(last_seen(&app), (0, 1, 2));
+
+    // Entities spawned later get new archetypes in the cached query.
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    worlds
+        .world_mut(Sim)
+        .unwrap()
+        .spawn((Health(30), Name::new("late")));
+    app.update();
+    assert_eq!(last_seen(&app), (1, 1, 3));
+}
+
+#[test]
+fn reset_worlds_are_queried_from_scratch() {
+    let mut app = app();
+    tick(&mut app);
+    tick(&mut app);
+    assert_eq!(last_seen(&app), (0, 0, 2));
+
+    run(&mut app, |commands| commands.reset_world(Sim));
+    app.update();
+    assert_eq!(last_seen(&app), (0, 0, 0));
+    tick(&mut app);
+    assert_eq!(last_seen(&app), (2, 2, 2));
+    app.update();
+    assert_eq!(last_seen(&app), (0, 0, 2));
+}
+
+#[test]
+fn replaced_worlds_are_queried_from_scratch() {
+    let mut app = app();
+    tick(&mut app);
+    tick(&mut app);
+
+    run(&mut app, |commands| {
+        commands.remove_world(Sim);
+        commands.add_world(Sim, Level);
+    });
+    tick(&mut app);
+    assert_eq!(last_seen(&app), (2, 2, 2));
+    app.update();
+    assert_eq!(last_seen(&app), (0, 0, 2));
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use whitelace_sync::{
+    IntoSyncSystemConfig, MultiworldApp, SyncPlugin, WorldLabel, WorldResMut, Worlds,
+};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct First;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct Last;
+
+#[derive(Resource, Default)]
+struct Log(Vec<&'static str>);
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .init_resource::<Log>();
+    app
+}
+
+#[test]
+fn conditions_see_the_worlds() {
+    let mut app = app();
+    app.add_sync_system(
+        (|mut log: WorldResMut<Log>| log.0.push("ran"))
+            .run_if(|worlds: Res<Worlds>| worlds.contains(Sim)),
+    );
+    app.update();
+    assert_eq!(app.world().resource::<Log>().0, ["ran"]);
+}
+
+#[test]
+fn ordering_takes_system_sets() {
+    let mut app = app();
+    app.add_sync_system((|mut log: WorldResMut<Log>| log.0.push("second")).after(First))
+        .add_sync_system((|mut log: WorldResMut<Log>| log.0.push("first")).in_set(First))
+        .add_sync_system((|mut log: WorldResMut<Log>| log.0.push("zeroth")).before(First));
+    app.update();
+    assert_eq!(
+        app.world().resource::<Log>().0,
+        ["zeroth", "first", "second"]
+    );
+}
+
+#[test]
+fn ready_systems_keep_their_insertion_order() {
+    let mut app = app();
+    app.add_sync_system((|mut log: WorldResMut<Log>| log.0.push("a")).after(Last))
+        .add_sync_system(|mut log: WorldResMut<Log>| log.0.push("b"))
+        .add_sync_system((|mut log: WorldResMut<Log>| log.0.push("c")).in_set(Last))
+        .add_sync_system((|mut log: WorldResMut<Log>| log.0.push("d")).before(Last));
+    app.update();
+    assert_eq!(app.world().resource::<Log>().0, ["b", "d", "c", "a"]);
+}
+
+#[test]
+#[should_panic = "cycle in their before/after constraints"]
+fn cycles_panic() {
+    let mut app = app();
+    app.add_sync_system(
+        (|mut log: WorldResMut<Log>| log.0.push("first"))
+            .in_set(First)
+            .after(Last),
+    )
+    .add_sync_system(
+        (|mut log: WorldResMut<Log>| log.0.push("last"))
+            .in_set(Last)
+            .after(First),
+    );
+    app.update();
+}
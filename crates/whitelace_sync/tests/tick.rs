@@ -0,0 +1,128 @@
+use bevy::{ecs::intern::Interned, prelude::*};
+use whitelace_core::main::{
+    Subworld,
+    input::{FrameInput, UserInput},
+    schedule::{FixedUpdate, SimTick},
+};
+use whitelace_sync::{MultiworldApp, SyncPlugin, TickBudget, WorldLabel, Worlds};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct Lane(u32);
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Plain;
+
+#[derive(Debug, Clone, Copy)]
+struct Push(u64);
+
+impl UserInput for Push {}
+
+#[derive(Resource, Default)]
+struct Total(u64);
+
+fn accumulate(input: Res<FrameInput<Push>>, tick: Res<SimTick>, mut total: ResMut<Total>) {
+    for push in input.get() {
+        total.0 = total.0 * 31 + push.0 + tick.get();
+    }
+}
+
+fn push(label: Interned<dyn WorldLabel>, tick: SimTick) -> Vec<Push> {
+    let lane = (0..4)
+        .position(|lane| Lane(lane).intern() == label)
+        .unwrap();
+    vec![Push(lane as u64), Push(tick.get() * 7)]
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(SyncPlugin).add_world(Plain);
+    for lane in 0..4 {
+        app.add_world_with_input::<Push>(Lane(lane))
+            .modify_world_with_input::<Push>(Lane(lane), |world| {
+                world.init_resource::<Total>();
+            })
+            .add_world_systems(Lane(lane), FixedUpdate, accumulate);
+    }
+    app
+}
+
+fn total(app: &App, lane: u32) -> u64 {
+    let worlds = app.world().resource::<Worlds>();
+    worlds
+        .get_with_input::<Push>(Lane(lane))
+        .unwrap()
+        .resource::<Total>()
+        .0
+}
+
+#[test]
+fn parallel_input_ticks_match_sequential() {
+    let mut app = app();
+    app.world_mut()
+        .resource_mut::<Worlds>()
+        .tick_worlds_with_input((0..4).map(Lane), 5, push);
+
+    for lane in 0..4 {
+        let mut world = Subworld::<Push>::default();
+        world.init_resource::<Total>();
+        world.add_systems(FixedUpdate, accumulate);
+        for _ in 0..5 {
+            let tick = *world.resource::<SimTick>();
+            world.tick(push(Lane(lane).intern(), tick));
+        }
+        assert_eq!(total(&app, lane), world.resource::<Total>().0);
+    }
+    let worlds = app.world().resource::<Worlds>();
+    assert_eq!(worlds.get(Plain).unwrap().resource::<SimTick>().get(), 0);
+}
+
+#[test]
+fn budgets_keep_input_ticks_pending() {
+    let mut app = app();
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    worlds.set_tick_budget(
+        Lane(0),
+        TickBudget {
+            max_ticks: 2,
+            max_time: None,
+        },
+    );
+    worlds.tick_worlds_with_input([Lane(0)], 3, push);
+    assert_eq!(worlds.tick_stats(Lane(0)).unwrap().pending_ticks, 1);
+    worlds.tick_worlds_with_input([Lane(0)], 0, push);
+    let world = worlds.get_with_input::<Push>(Lane(0)).unwrap();
+    assert_eq!(world.resource::<SimTick>().get(), 3);
+}
+
+#[test]
+#[should_panic = "has the input type `()`"]
+fn input_type_mismatch_panics() {
+    let mut app = app();
+    app.world_mut()
+        .resource_mut::<Worlds>()
+        .tick_worlds_with_input([Plain], 1, |_, _| Vec::<Push>::new());
+}
+
+#[test]
+#[cfg(feature = "multi_threaded")]
+fn multi_threaded_worlds_tick_with_the_others() {
+    use whitelace_core::main::schedule::SubworldExecutor;
+
+    let mut parallel = app();
+    parallel.modify_world_with_input::<Push>(Lane(1), |world| {
+        world.set_executor(SubworldExecutor::MultiThreaded);
+    });
+    parallel
+        .world_mut()
+        .resource_mut::<Worlds>()
+        .tick_worlds_with_input((0..4).map(Lane), 5, push);
+
+    let mut expected = app();
+    expected
+        .world_mut()
+        .resource_mut::<Worlds>()
+        .tick_worlds_with_input((0..4).map(Lane), 5, push);
+    for lane in 0..4 {
+        assert_eq!(total(&parallel, lane), total(&expected, lane));
+    }
+}
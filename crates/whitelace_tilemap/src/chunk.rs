@@ -5,27 +5,33 @@ use bevy::{
         hierarchy::ChildOf,
         name::Name,
         query::Changed,
+        reflect::ReflectComponent,
         system::{Commands, Query},
     },
     platform::prelude::vec::Vec,
     prelude::vec,
     reflect::Reflect,
 };
+use whitelace_core::main::{Checksum, Snapshot};
 use whitelace_transform::FixedTransform;
 
 use crate::{TilemapSize, TilemapStorage};
 
-#[derive(Component, Reflect, Default)]
+#[derive(Component, Reflect, Snapshot, Checksum, Default)]
+#[reflect(Component)]
 #[relationship_target(relationship = AttachedToChunk, linked_spawn)]
 pub struct ChunkStorage(Vec<Entity>);
 
 #[derive(Component, Reflect)]
+#[reflect(Component)]
 #[relationship(relationship_target = ChunkStorage)]
 pub struct AttachedToChunk(Entity);
 
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Snapshot, Checksum)]
+#[reflect(Component)]
 #[require(FixedTransform)]
 pub struct Chunk {
+    #[entities]
     tiles: Vec<Entity>,
 }
 
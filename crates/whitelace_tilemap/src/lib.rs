@@ -6,15 +6,19 @@ mod chunk;
 
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use whitelace_core::{main::schedule::FixedUpdate, math::Fx};
-use whitelace_physics::collision::Collider;
+use whitelace_core::{
+    main::{Checksum, DPlugin, Snapshot, Subworld, input::UserInput, schedule::FixedUpdate},
+    math::Fx,
+};
+use whitelace_physics::{PhysicsDPlugin, PhysicsPlugin, collision::Collider};
 use whitelace_sync::{MultiworldApp, WorldLabel};
 use whitelace_transform::FixedTransform;
 
-use crate::chunk::split_by_chunks;
+use crate::chunk::{AttachedToChunk, split_by_chunks};
 pub use crate::chunk::{Chunk, ChunkStorage};
 
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Snapshot, Checksum)]
+#[reflect(Component)]
 pub struct TilemapSize {
     width: u32,
     height: u32,
@@ -27,9 +31,9 @@ impl TilemapSize {
     }
 }
 
-#[derive(Component, Reflect, Serialize, Deserialize, Debug, Clone)]
+#[derive(Component, Reflect, Serialize, Deserialize, Snapshot, Checksum, Debug, Clone)]
 #[reflect(opaque)]
-#[reflect(Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
 pub struct TileSize {
     x: Fx,
     y: Fx,
@@ -45,19 +49,21 @@ impl TileSize {
     }
 }
 
-#[derive(Component, Reflect, Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Component, Reflect, Serialize, Deserialize, Snapshot, Checksum, Default, Debug, Clone)]
 #[reflect(opaque)]
-#[reflect(Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
 pub struct TilePadding {
     pub x: Fx,
     pub y: Fx,
 }
 
-#[derive(Component, Reflect, Deref, DerefMut)]
+#[derive(Component, Reflect, Snapshot, Checksum, Deref, DerefMut)]
+#[reflect(Component)]
 #[require(FixedTransform)]
 pub struct TileIndex(pub u32);
 
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Snapshot, Checksum)]
+#[reflect(Component)]
 pub struct TilePosition {
     x: u32,
     y: u32,
@@ -79,9 +85,10 @@ pub struct TilemapBundle {
     //pub position: FVec3,
 }
 
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Snapshot, Checksum)]
+#[reflect(Component)]
 #[require(ChunkStorage)]
-pub struct TilemapStorage(Vec<Option<Entity>>);
+pub struct TilemapStorage(#[entities] Vec<Option<Entity>>);
 
 impl TilemapStorage {
     #[must_use]
@@ -157,20 +164,78 @@ impl OnChunkSpawn for CollisionBackend {
     }
 }
 
-pub struct TilemapPlugin<W: WorldLabel> {
-    _phantom: core::marker::PhantomData<W>,
-}
+/// Deterministic part of [`TilemapPlugin`], installed directly into a [`Subworld`].
+///
+/// Chunks get a [`Collider`], so it requires the [`PhysicsDPlugin`].
+#[derive(Default)]
+pub struct TilemapDPlugin;
 
-impl<W: WorldLabel + Default> Plugin for TilemapPlugin<W> {
-    fn build(&self, app: &mut App) {
-        app.add_world_systems(
-            W::default(),
+impl<I: UserInput> DPlugin<I> for TilemapDPlugin {
+    fn build(&self, world: &mut Subworld<I>) {
+        world.require_plugin::<PhysicsDPlugin>();
+
+        world.add_systems(
             FixedUpdate,
             (
                 set_tiles_position,
                 split_by_chunks,
                 on_chunk_spawn::<CollisionBackend>,
-            ),
+            )
+                .chain(),
         );
+        world
+            .register_entity_mapping::<Chunk>()
+            .register_entity_mapping::<TilemapStorage>()
+            .register_type::<TilemapSize>()
+            .register_type::<TileSize>()
+            .register_type::<TilePadding>()
+            .register_type::<TileIndex>()
+            .register_type::<TilePosition>()
+            .register_type::<TilemapStorage>()
+            .register_type::<Chunk>()
+            .register_type::<ChunkStorage>()
+            .register_type::<AttachedToChunk>()
+            .register_snapshot::<TilemapSize>()
+            .register_snapshot::<TileSize>()
+            .register_snapshot::<TilePadding>()
+            .register_snapshot::<TileIndex>()
+            .register_snapshot::<TilePosition>()
+            .register_snapshot::<TilemapStorage>()
+            .register_snapshot::<Chunk>()
+            .register_snapshot::<ChunkStorage>()
+            .register_checksum::<TilemapSize>()
+            .register_checksum::<TileSize>()
+            .register_checksum::<TilePadding>()
+            .register_checksum::<TileIndex>()
+            .register_checksum::<TilePosition>()
+            .register_checksum::<TilemapStorage>()
+            .register_checksum::<Chunk>()
+            .register_checksum::<ChunkStorage>();
+    }
+}
+
+/// Adds [`TilemapDPlugin`] to the world `W`, whose input type is `I`, along with the
+/// [`PhysicsPlugin`] if it isn't added yet.
+pub struct TilemapPlugin<W: WorldLabel, I: UserInput = ()> {
+    _phantom: core::marker::PhantomData<(W, I)>,
+}
+
+impl<W: WorldLabel, I: UserInput> Default for TilemapPlugin<W, I> {
+    fn default() -> Self {
+        Self {
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<W: WorldLabel + Default, I: UserInput> Plugin for TilemapPlugin<W, I> {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PhysicsPlugin<W, I>>() {
+            app.add_plugins(PhysicsPlugin::<W, I>::default());
+        }
+
+        app.modify_world_with_input::<I>(W::default(), |world| {
+            world.require_plugin::<TilemapDPlugin>();
+        });
     }
 }
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use whitelace_core::main::{Checksum, Snapshot};
+use whitelace_math::Fx;
+
+use crate::{FixedTimer, FixedTimerMode, Time, TimerClock};
+
+/// An ability-style cooldown: ready until [triggered](Cooldown::trigger), then ready again once
+/// its duration passes.
+///
+/// Components are advanced by [`TimeDPlugin`](crate::TimeDPlugin) in `PreFixedUpdate`.
+#[derive(Component, Reflect, Snapshot, Checksum, Debug, Default, Clone, PartialEq, Eq)]
+#[reflect(opaque)]
+pub struct Cooldown {
+    timer: FixedTimer,
+}
+
+impl Cooldown {
+    /// Creates a ready cooldown of `duration` seconds.
+    #[must_use]
+    pub fn new(duration: Fx) -> Self {
+        let mut timer = FixedTimer::new(duration, FixedTimerMode::Once);
+        timer.finish();
+        Self { timer }
+    }
+
+    /// Creates a ready cooldown of `ticks` ticks.
+    #[must_use]
+    pub fn from_ticks(ticks: u32) -> Self {
+        let mut timer = FixedTimer::from_ticks(ticks, FixedTimerMode::Once);
+        timer.finish();
+        Self { timer }
+    }
+
+    /// Starts the cooldown if it is ready. Returns whether it was.
+    pub fn trigger(&mut self) -> bool {
+        let ready = self.is_ready();
+        if ready {
+            self.timer.reset();
+        }
+        ready
+    }
+
+    /// Starts the cooldown over, even if it isn't ready.
+    pub const fn restart(&mut self) {
+        self.timer.reset();
+    }
+
+    /// Makes the cooldown ready immediately.
+    pub const fn make_ready(&mut self) {
+        self.timer.finish();
+    }
+
+    pub fn tick(&mut self, time: &Time) -> &Self {
+        self.timer.tick(time);
+        self
+    }
+
+    pub fn tick_by(&mut self, delta: Fx) -> &Self {
+        self.timer.tick_by(delta);
+        self
+    }
+
+    #[must_use]
+    pub const fn is_ready(&self) -> bool {
+        self.timer.is_finished()
+    }
+
+    /// Whether the cooldown became ready during the last tick.
+    #[must_use]
+    pub const fn just_ready(&self) -> bool {
+        self.timer.just_finished()
+    }
+
+    #[must_use]
+    pub fn remaining(&self) -> Fx {
+        self.timer.remaining()
+    }
+
+    #[must_use]
+    pub const fn duration(&self) -> Fx {
+        self.timer.duration()
+    }
+
+    pub const fn set_duration(&mut self, duration: Fx) {
+        self.timer.set_duration(duration);
+    }
+
+    #[must_use]
+    pub const fn clock(&self) -> TimerClock {
+        self.timer.clock()
+    }
+
+    pub const fn pause(&mut self) {
+        self.timer.pause();
+    }
+
+    pub const fn unpause(&mut self) {
+        self.timer.unpause();
+    }
+
+    #[must_use]
+    pub const fn is_paused(&self) -> bool {
+        self.timer.is_paused()
+    }
+
+    #[must_use]
+    pub const fn timer(&self) -> &FixedTimer {
+        &self.timer
+    }
+}
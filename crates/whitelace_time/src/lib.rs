@@ -2,11 +2,25 @@
 #![allow(clippy::needless_pass_by_value)]
 #![no_std]
 
+mod cooldown;
+mod stopwatch;
+mod timer;
+
 use bevy::prelude::*;
+use whitelace_core::main::{DPlugin, Subworld, input::UserInput, schedule::PreFixedUpdate};
 use whitelace_math::{Fx, fx};
-use whitelace_sync::{MultiworldApp, WorldLabel, WorldRes, WorldResMut};
+use whitelace_sync::{
+    IntoSyncSystemConfig, MultiworldApp, SyncPoint, WorldLabel, WorldRes, WorldResMut,
+};
+
+pub use crate::{
+    cooldown::Cooldown,
+    stopwatch::Stopwatch,
+    timer::{FixedTimer, FixedTimerMode, TimerClock},
+};
 
-#[derive(Resource, Default)]
+#[derive(Resource, Reflect, Default, Clone)]
+#[reflect(opaque)]
 pub struct Time {
     delta_time: Fx,
 }
@@ -19,11 +33,41 @@ impl Time {
     }
 }
 
-pub struct TimePlugin<W: WorldLabel> {
-    _phantom: core::marker::PhantomData<W>,
+/// The sync system of [`TimePlugin`] copying the delta time into its world, at
+/// [`SyncPoint::BeforeTick`], so other sync systems can be ordered against it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SyncTimeSet;
+
+/// Deterministic part of [`TimePlugin`], installed directly into a [`Subworld`].
+#[derive(Default)]
+pub struct TimeDPlugin;
+
+impl<I: UserInput> DPlugin<I> for TimeDPlugin {
+    fn build(&self, world: &mut Subworld<I>) {
+        world.init_resource::<Time>();
+        world.add_systems(PreFixedUpdate, tick_timers);
+        // Opaque for reflection, the fields are all fixed point.
+        world
+            .register_type::<Time>()
+            .register_type::<FixedTimer>()
+            .register_type::<Cooldown>()
+            .register_type::<Stopwatch>()
+            .register_fork_resource::<Time>()
+            .register_snapshot::<FixedTimer>()
+            .register_snapshot::<Cooldown>()
+            .register_snapshot::<Stopwatch>()
+            .register_checksum::<FixedTimer>()
+            .register_checksum::<Cooldown>()
+            .register_checksum::<Stopwatch>();
+    }
+}
+
+/// Adds [`TimeDPlugin`] to the world `W`, whose input type is `I`.
+pub struct TimePlugin<W: WorldLabel, I: UserInput = ()> {
+    _phantom: core::marker::PhantomData<(W, I)>,
 }
 
-impl<W: WorldLabel> Default for TimePlugin<W> {
+impl<W: WorldLabel, I: UserInput> Default for TimePlugin<W, I> {
     fn default() -> Self {
         Self {
             _phantom: core::marker::PhantomData,
@@ -31,12 +75,13 @@ impl<W: WorldLabel> Default for TimePlugin<W> {
     }
 }
 
-impl<W: WorldLabel + Default> Plugin for TimePlugin<W> {
+impl<W: WorldLabel + Default, I: UserInput> Plugin for TimePlugin<W, I> {
     fn build(&self, app: &mut App) {
-        app.modify_world(W::default(), |world| {
-            world.init_resource::<Time>();
+        app.modify_world_with_input::<I>(W::default(), |world| {
+            world.require_plugin::<TimeDPlugin>();
         });
-        app.add_sync_system(sync_time::<W>);
+        // The delta time is read by the tick, so it has to be there before.
+        app.add_sync_system(sync_time::<W>.at(SyncPoint::BeforeTick).in_set(SyncTimeSet));
     }
 }
 
@@ -47,3 +92,20 @@ fn sync_time<W: WorldLabel>(
 ) {
     to.delta_time = fx!(from.delta_secs_f64());
 }
+
+fn tick_timers(
+    time: Res<Time>,
+    mut timers: Query<&mut FixedTimer>,
+    mut cooldowns: Query<&mut Cooldown>,
+    mut stopwatches: Query<&mut Stopwatch>,
+) {
+    for mut timer in &mut timers {
+        timer.tick(&time);
+    }
+    for mut cooldown in &mut cooldowns {
+        cooldown.tick(&time);
+    }
+    for mut stopwatch in &mut stopwatches {
+        stopwatch.tick(&time);
+    }
+}
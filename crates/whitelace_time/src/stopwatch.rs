@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use whitelace_core::main::{Checksum, Snapshot};
+use whitelace_math::Fx;
+
+use crate::{Time, TimerClock};
+
+/// Measures elapsed time, or ticks, since it was started.
+///
+/// Components are advanced by [`TimeDPlugin`](crate::TimeDPlugin) in `PreFixedUpdate`.
+#[derive(Component, Reflect, Snapshot, Checksum, Debug, Default, Clone, PartialEq, Eq)]
+#[reflect(opaque)]
+pub struct Stopwatch {
+    elapsed: Fx,
+    clock: TimerClock,
+    paused: bool,
+}
+
+impl Stopwatch {
+    /// Creates a stopwatch counting seconds.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_clock(TimerClock::Time)
+    }
+
+    #[must_use]
+    pub const fn with_clock(clock: TimerClock) -> Self {
+        Self {
+            elapsed: Fx::ZERO,
+            clock,
+            paused: false,
+        }
+    }
+
+    pub fn tick(&mut self, time: &Time) -> &Self {
+        self.tick_by(self.clock.delta(time))
+    }
+
+    pub fn tick_by(&mut self, delta: Fx) -> &Self {
+        if !self.paused {
+            self.elapsed = self.elapsed.saturating_add(delta);
+        }
+        self
+    }
+
+    #[must_use]
+    pub const fn elapsed(&self) -> Fx {
+        self.elapsed
+    }
+
+    pub const fn set_elapsed(&mut self, elapsed: Fx) {
+        self.elapsed = elapsed;
+    }
+
+    #[must_use]
+    pub const fn clock(&self) -> TimerClock {
+        self.clock
+    }
+
+    pub const fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub const fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    #[must_use]
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub const fn reset(&mut self) {
+        self.elapsed = Fx::ZERO;
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use whitelace_core::main::{Checksum, Snapshot};
+use whitelace_math::Fx;
+
+use crate::Time;
+
+/// What advances a [`FixedTimer`], [`Cooldown`](crate::Cooldown) or
+/// [`Stopwatch`](crate::Stopwatch).
+#[derive(Snapshot, Checksum, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimerClock {
+    /// Advances by [`Time::delta_time`], durations are in seconds.
+    #[default]
+    Time,
+    /// Advances by one every tick, durations are in ticks.
+    Ticks,
+}
+
+impl TimerClock {
+    #[must_use]
+    pub const fn delta(self, time: &Time) -> Fx {
+        match self {
+            Self::Time => time.delta_time(),
+            Self::Ticks => Fx::ONE,
+        }
+    }
+}
+
+#[derive(Snapshot, Checksum, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FixedTimerMode {
+    /// Finishes once and stays finished until reset.
+    #[default]
+    Once,
+    /// Starts over every time it finishes.
+    Repeating,
+}
+
+/// A fixed point replacement of bevy's `Timer`, safe to use in a [`Subworld`].
+///
+/// Components are advanced by [`TimeDPlugin`](crate::TimeDPlugin) in `PreFixedUpdate`, timers
+/// stored anywhere else have to be advanced with [`FixedTimer::tick`].
+///
+/// Negative durations are taken as zero. A timer with a zero duration finishes on every tick it
+/// is advanced, once per tick even when repeating.
+///
+/// [`Subworld`]: whitelace_core::main::Subworld
+#[derive(Component, Reflect, Snapshot, Checksum, Debug, Default, Clone, PartialEq, Eq)]
+#[reflect(opaque)]
+pub struct FixedTimer {
+    duration: Fx,
+    elapsed: Fx,
+    mode: FixedTimerMode,
+    clock: TimerClock,
+    paused: bool,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl FixedTimer {
+    /// Creates a timer of `duration` seconds.
+    #[must_use]
+    pub const fn new(duration: Fx, mode: FixedTimerMode) -> Self {
+        Self {
+            duration: non_negative(duration),
+            elapsed: Fx::ZERO,
+            mode,
+            clock: TimerClock::Time,
+            paused: false,
+            finished: false,
+            times_finished_this_tick: 0,
+        }
+    }
+
+    /// Creates a timer of `ticks` ticks. Counts beyond the range of [`Fx`] saturate.
+    #[must_use]
+    pub fn from_ticks(ticks: u32, mode: FixedTimerMode) -> Self {
+        Self {
+            clock: TimerClock::Ticks,
+            ..Self::new(Fx::saturating_from_num(ticks), mode)
+        }
+    }
+
+    /// Advances the timer by one tick of its [`TimerClock`].
+    pub fn tick(&mut self, time: &Time) -> &Self {
+        self.tick_by(self.clock.delta(time))
+    }
+
+    /// Advances the timer by `delta`, in the units of its [`TimerClock`].
+    pub fn tick_by(&mut self, delta: Fx) -> &Self {
+        self.times_finished_this_tick = 0;
+        if self.paused || (self.finished && self.mode == FixedTimerMode::Once) {
+            return self;
+        }
+
+        self.elapsed = self.elapsed.saturating_add(delta);
+        self.finished = self.elapsed >= self.duration;
+        if !self.finished {
+            return self;
+        }
+
+        match self.mode {
+            FixedTimerMode::Once => {
+                self.elapsed = self.duration;
+                self.times_finished_this_tick = 1;
+            }
+            FixedTimerMode::Repeating if self.duration == Fx::ZERO => {
+                self.elapsed = Fx::ZERO;
+                self.times_finished_this_tick = 1;
+            }
+            FixedTimerMode::Repeating => {
+                // Both are positive, so dividing the bits is exact and can't overflow. Saturates
+                // when a huge delta wraps a tiny duration more times than fit.
+                let times = self.elapsed.to_bits() / self.duration.to_bits();
+                self.elapsed %= self.duration;
+                self.times_finished_this_tick = u32::try_from(times).unwrap_or(u32::MAX);
+            }
+        }
+        self
+    }
+
+    #[must_use]
+    pub const fn duration(&self) -> Fx {
+        self.duration
+    }
+
+    pub const fn set_duration(&mut self, duration: Fx) {
+        self.duration = non_negative(duration);
+    }
+
+    #[must_use]
+    pub const fn elapsed(&self) -> Fx {
+        self.elapsed
+    }
+
+    pub const fn set_elapsed(&mut self, elapsed: Fx) {
+        self.elapsed = elapsed;
+    }
+
+    #[must_use]
+    pub fn remaining(&self) -> Fx {
+        (self.duration - self.elapsed).max(Fx::ZERO)
+    }
+
+    /// How far the timer is from 0 to 1. A timer with zero duration is always at 1.
+    #[must_use]
+    pub fn fraction(&self) -> Fx {
+        if self.duration == Fx::ZERO {
+            Fx::ONE
+        } else {
+            self.elapsed / self.duration
+        }
+    }
+
+    #[must_use]
+    pub const fn mode(&self) -> FixedTimerMode {
+        self.mode
+    }
+
+    pub const fn set_mode(&mut self, mode: FixedTimerMode) {
+        self.mode = mode;
+    }
+
+    #[must_use]
+    pub const fn clock(&self) -> TimerClock {
+        self.clock
+    }
+
+    pub const fn set_clock(&mut self, clock: TimerClock) {
+        self.clock = clock;
+    }
+
+    pub const fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub const fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    #[must_use]
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Whether the timer has reached its duration. A repeating timer is only finished on the
+    /// ticks it wrapped around.
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Whether the timer finished during the last tick.
+    #[must_use]
+    pub const fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    /// How many times the timer finished during the last tick. Can be more than one for
+    /// repeating timers shorter than a tick.
+    #[must_use]
+    pub const fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    /// Starts the timer over. Doesn't unpause it.
+    pub const fn reset(&mut self) {
+        self.elapsed = Fx::ZERO;
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+
+    /// Makes the timer finished without reporting it as [just finished](Self::just_finished).
+    pub const fn finish(&mut self) {
+        self.elapsed = self.duration;
+        self.finished = true;
+        self.times_finished_this_tick = 0;
+    }
+}
+
+const fn non_negative(duration: Fx) -> Fx {
+    if duration.is_negative() {
+        Fx::ZERO
+    } else {
+        duration
+    }
+}
@@ -0,0 +1,36 @@
+#![allow(clippy::disallowed_types)]
+
+use core::time::Duration;
+
+use bevy::prelude::*;
+use whitelace_math::Fx;
+use whitelace_sync::{
+    IntoSyncSystemConfig, MultiworldApp, SyncPlugin, SyncPoint, WorldLabel, WorldRes, WorldResMut,
+};
+use whitelace_time::{SyncTimeSet, TimePlugin};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+#[derive(Resource, Default)]
+struct Seen(Fx);
+
+fn record(time: WorldRes<whitelace_time::Time, Sim>, mut seen: WorldResMut<Seen>) {
+    seen.0 = time.delta_time();
+}
+
+#[test]
+fn sync_systems_order_after_the_time_sync() {
+    let mut app = App::new();
+    let mut fixed = Time::<Fixed>::default();
+    fixed.advance_by(Duration::from_millis(250));
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .init_resource::<Seen>()
+        .insert_resource(fixed)
+        // Added before the plugin, so it would see the delta of the last tick without the set.
+        .add_sync_system(record.at(SyncPoint::BeforeTick).after(SyncTimeSet))
+        .add_plugins(TimePlugin::<Sim>::default());
+    app.update();
+    assert_eq!(app.world().resource::<Seen>().0, Fx::from_num(0.25));
+}
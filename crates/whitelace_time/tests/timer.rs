@@ -0,0 +1,132 @@
+use whitelace_math::Fx;
+use whitelace_time::{Cooldown, FixedTimer, FixedTimerMode, Stopwatch, TimerClock};
+
+fn fx(value: f64) -> Fx {
+    Fx::from_num(value)
+}
+
+#[test]
+fn repeating_timers_wrap_around() {
+    let mut timer = FixedTimer::new(Fx::ONE, FixedTimerMode::Repeating);
+    timer.tick_by(fx(2.5));
+    assert_eq!(timer.times_finished_this_tick(), 2);
+    assert_eq!(timer.elapsed(), fx(0.5));
+
+    timer.tick_by(fx(0.25));
+    assert!(!timer.just_finished());
+    assert!(!timer.is_finished());
+
+    timer.tick_by(fx(0.25));
+    assert_eq!(timer.times_finished_this_tick(), 1);
+    assert_eq!(timer.elapsed(), Fx::ZERO);
+}
+
+#[test]
+fn just_finished_follows_every_wrap() {
+    let mut timer = FixedTimer::from_ticks(3, FixedTimerMode::Repeating);
+    let finished: Vec<bool> = (0..7)
+        .map(|_| timer.tick_by(Fx::ONE).just_finished())
+        .collect();
+    assert_eq!(finished, [false, false, true, false, false, true, false]);
+    assert_eq!(timer.elapsed(), Fx::ONE);
+}
+
+#[test]
+fn huge_deltas_and_counts_saturate() {
+    let mut timer = FixedTimer::new(Fx::DELTA, FixedTimerMode::Repeating);
+    timer.tick_by(Fx::MAX);
+    assert_eq!(timer.times_finished_this_tick(), u32::MAX);
+    timer.tick_by(Fx::MAX);
+    assert!(timer.just_finished());
+
+    let timer = FixedTimer::from_ticks(u32::MAX, FixedTimerMode::Once);
+    assert_eq!(timer.duration(), Fx::MAX);
+
+    let mut stopwatch = Stopwatch::new();
+    stopwatch.tick_by(Fx::MAX);
+    stopwatch.tick_by(Fx::MAX);
+    assert_eq!(stopwatch.elapsed(), Fx::MAX);
+}
+
+#[test]
+fn paused_timers_keep_their_progress() {
+    let mut timer = FixedTimer::from_ticks(2, FixedTimerMode::Once);
+    timer.tick_by(Fx::ONE);
+    timer.pause();
+    timer.tick_by(Fx::ONE);
+    timer.tick_by(Fx::ONE);
+    assert!(timer.is_paused());
+    assert_eq!(timer.elapsed(), Fx::ONE);
+    assert!(!timer.is_finished());
+
+    timer.unpause();
+    assert!(timer.tick_by(Fx::ONE).just_finished());
+    assert!(!timer.tick_by(Fx::ONE).just_finished());
+    assert!(timer.is_finished());
+    assert_eq!(timer.elapsed(), fx(2.0));
+}
+
+#[test]
+fn zero_durations_finish_once_per_tick() {
+    let mut repeating = FixedTimer::from_ticks(0, FixedTimerMode::Repeating);
+    for _ in 0..3 {
+        assert_eq!(repeating.tick_by(Fx::ONE).times_finished_this_tick(), 1);
+    }
+    assert_eq!(repeating.fraction(), Fx::ONE);
+
+    let mut once = FixedTimer::new(Fx::ZERO, FixedTimerMode::Once);
+    assert!(!once.is_finished());
+    assert!(once.tick_by(Fx::ZERO).just_finished());
+    assert!(!once.tick_by(Fx::ZERO).just_finished());
+
+    let negative = FixedTimer::new(-Fx::ONE, FixedTimerMode::Once);
+    assert_eq!(negative.duration(), Fx::ZERO);
+}
+
+#[test]
+fn cooldowns_restart_when_triggered() {
+    let mut cooldown = Cooldown::from_ticks(2);
+    assert!(cooldown.is_ready());
+    assert!(cooldown.trigger());
+    assert!(!cooldown.trigger());
+
+    cooldown.tick_by(Fx::ONE);
+    assert_eq!(cooldown.remaining(), Fx::ONE);
+    cooldown.restart();
+    assert_eq!(cooldown.remaining(), fx(2.0));
+
+    cooldown.tick_by(Fx::ONE);
+    assert!(!cooldown.just_ready());
+    cooldown.tick_by(Fx::ONE);
+    assert!(cooldown.just_ready());
+    assert!(cooldown.trigger());
+
+    cooldown.make_ready();
+    assert!(cooldown.is_ready());
+    assert!(!cooldown.just_ready());
+}
+
+#[test]
+fn zero_cooldowns_are_ready_on_the_next_tick() {
+    let mut cooldown = Cooldown::new(Fx::ZERO);
+    assert!(cooldown.trigger());
+    assert!(!cooldown.is_ready());
+    cooldown.tick_by(fx(0.1));
+    assert!(cooldown.just_ready());
+}
+
+#[test]
+fn stopwatches_pause_and_reset() {
+    let mut stopwatch = Stopwatch::with_clock(TimerClock::Ticks);
+    stopwatch.tick_by(Fx::ONE);
+    stopwatch.pause();
+    stopwatch.tick_by(Fx::ONE);
+    assert_eq!(stopwatch.elapsed(), Fx::ONE);
+
+    stopwatch.unpause();
+    stopwatch.tick_by(Fx::ONE);
+    assert_eq!(stopwatch.elapsed(), fx(2.0));
+    stopwatch.reset();
+    assert_eq!(stopwatch.elapsed(), Fx::ZERO);
+    assert!(!stopwatch.is_paused());
+}
@@ -2,16 +2,25 @@
 #![no_std]
 
 use bevy::prelude::*;
-use whitelace_core::main::schedule::PreFixedUpdate;
+use whitelace_core::main::{
+    Checksum, DPlugin, Snapshot, StableId, Subworld,
+    input::UserInput,
+    schedule::{FixedFirst, PreFixedUpdate},
+};
 use whitelace_math::{FDir3, FQuat, FVec3, IntoFx};
-use whitelace_sync::{MultiworldApp, SyncTarget, WorldLabel, WorldQuery};
+use whitelace_sync::{
+    IntoSyncSystemConfig, MultiworldApp, SyncTarget, SyncWorld, WorldLabel, WorldQuery, WorldRes,
+};
 
 pub mod prelude {
     pub use super::*;
 }
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[require(FixedGlobalTransform)]
+#[derive(
+    Component, Reflect, Snapshot, Checksum, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[reflect(Component, Default, Debug, PartialEq, Hash)]
+#[require(FixedGlobalTransform, StableId)]
 pub struct FixedTransform {
     pub position: FVec3,
     pub rotation: FQuat,
@@ -167,7 +176,21 @@ impl From<FixedTransform> for (FVec3, FQuat, FVec3) {
     }
 }
 
-#[derive(Component, Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Component,
+    Reflect,
+    Snapshot,
+    Checksum,
+    Default,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[reflect(Component, Default, Debug, PartialEq, Hash)]
 pub struct FixedGlobalTransform {
     position: FVec3,
     rotation: FQuat,
@@ -229,11 +252,76 @@ pub(crate) fn sync_fixed_transforms(
     }
 }
 
-pub struct TransformPlugin<W: WorldLabel> {
-    _marker: core::marker::PhantomData<W>,
+/// The [`FixedTransform`] of an entity at the start of the last tick, so the transform of the
+/// tick before the current one, which its visuals are interpolated from.
+///
+/// Remembered in [`FixedFirst`], before [`Physics`](whitelace_core::main::schedule::Physics)
+/// or any other schedule of the tick moves the entity, and inserted on its first tick. It's snapshotted, so a restored world interpolates
+/// from the right tick, but not checksummed, as the simulation never reads it.
+#[derive(Component, Reflect, Snapshot, Debug, Clone, PartialEq, Eq)]
+#[reflect(Component, Debug, PartialEq)]
+pub struct PreviousFixedTransform(pub FixedTransform);
+
+pub(crate) fn remember_fixed_transforms(
+    mut commands: Commands,
+    query: Query<(Entity, &FixedTransform, Option<&mut PreviousFixedTransform>)>,
+) {
+    for (entity, transform, previous) in query {
+        match previous {
+            Some(mut previous) => {
+                previous.set_if_neq(PreviousFixedTransform(transform.clone()));
+            }
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(PreviousFixedTransform(transform.clone()));
+            }
+        }
+    }
+}
+
+/// Deterministic part of [`TransformPlugin`], installed directly into a [`Subworld`].
+#[derive(Default)]
+pub struct TransformDPlugin;
+
+impl<I: UserInput> DPlugin<I> for TransformDPlugin {
+    fn build(&self, world: &mut Subworld<I>) {
+        world.add_systems(FixedFirst, remember_fixed_transforms);
+        world.add_systems(
+            PreFixedUpdate,
+            (sync_fixed_global_transforms, sync_fixed_transforms).chain(),
+        );
+        world
+            .register_type::<FixedTransform>()
+            .register_type::<FixedGlobalTransform>()
+            .register_type::<PreviousFixedTransform>()
+            .register_snapshot::<FixedTransform>()
+            .register_snapshot::<FixedGlobalTransform>()
+            .register_snapshot::<PreviousFixedTransform>()
+            .register_checksum::<FixedTransform>()
+            .register_checksum::<FixedGlobalTransform>();
+    }
+}
+
+/// The sync system of [`TransformPlugin`] copying the transforms of its world into the visuals,
+/// so other sync systems can be ordered against it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SyncTransformSet;
+
+/// Adds [`TransformDPlugin`] to the world `W`, whose input type is `I`, and syncs the
+/// transforms of its entities into their visuals, the main world entities with a
+/// [`SyncTarget`] to them and a [`SyncWorld`] of `W`.
+///
+/// Visuals are interpolated from the [`PreviousFixedTransform`] to the [`FixedTransform`] by
+/// the overstep of the main world's `Time<Fixed>`, however `W` is ticked. That only matches
+/// when `W` is ticked at the rate of `Time<Fixed>`; mark the visuals of a world ticked
+/// otherwise, e.g. by hand, with [`NoInterpolation`]. Without `Time<Fixed>` the visuals show the
+/// latest tick.
+pub struct TransformPlugin<W: WorldLabel, I: UserInput = ()> {
+    _marker: core::marker::PhantomData<(W, I)>,
 }
 
-impl<W: WorldLabel> Default for TransformPlugin<W> {
+impl<W: WorldLabel, I: UserInput> Default for TransformPlugin<W, I> {
     fn default() -> Self {
         Self {
             _marker: core::marker::PhantomData,
@@ -241,28 +329,59 @@ impl<W: WorldLabel> Default for TransformPlugin<W> {
     }
 }
 
-impl<W: WorldLabel + Default> Plugin for TransformPlugin<W> {
+impl<W: WorldLabel + Default, I: UserInput> Plugin for TransformPlugin<W, I> {
     fn build(&self, app: &mut App) {
-        app.add_world_systems(
-            W::default(),
-            PreFixedUpdate,
-            (sync_fixed_global_transforms, sync_fixed_transforms),
-        );
+        app.modify_world_with_input::<I>(W::default(), |world| {
+            world.require_plugin::<TransformDPlugin>();
+        });
 
-        app.add_sync_system(sync_transform::<W>);
+        app.add_sync_system(sync_transform::<W>.in_set(SyncTransformSet));
     }
 }
 
-fn sync_transform<W: WorldLabel>(
-    from: WorldQuery<&FixedTransform, (), W>,
-    mut to: WorldQuery<(&mut Transform, &SyncTarget)>,
-) {
-    for (mut transform, sync) in &mut to.iter_mut() {
-        let fixed_transform = from.get(sync.0).unwrap();
+/// Marks a visual that snaps to the latest tick instead of being interpolated, e.g. while it
+/// teleports.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct NoInterpolation;
+
+fn visual_transform(fixed_transform: &FixedTransform) -> Transform {
+    Transform {
+        translation: fixed_transform.position.as_vec3() + fixed_transform.size.as_vec3() / 2.0,
+        rotation: fixed_transform.rotation.as_quat(),
+        scale: fixed_transform.size.as_vec3(),
+    }
+}
 
-        transform.translation =
-            fixed_transform.position.as_vec3() + fixed_transform.size.as_vec3() / 2.0;
-        transform.scale = fixed_transform.size.as_vec3();
-        transform.rotation = fixed_transform.rotation.as_quat();
+#[allow(clippy::disallowed_types)]
+fn sync_transform<W: WorldLabel + Default>(
+    from: WorldQuery<(&FixedTransform, Option<&PreviousFixedTransform>), (), W>,
+    fixed_time: Option<WorldRes<Time<Fixed>>>,
+    mut to: WorldQuery<(
+        &mut Transform,
+        &SyncTarget,
+        &SyncWorld,
+        Has<NoInterpolation>,
+    )>,
+) {
+    let label = W::default().intern();
+    let overstep = fixed_time.map_or(1.0, |time| time.overstep_fraction());
+    for (mut transform, sync, world, snap) in &mut to.iter_mut() {
+        if world.0 != label {
+            continue;
+        }
+        // Orphaned visuals are handled by the `OrphanPolicy` of `SyncPlugin`.
+        let Ok((fixed_transform, previous)) = from.get(sync.0) else {
+            continue;
+        };
+        let current = visual_transform(fixed_transform);
+        let previous = match previous {
+            Some(previous) if !snap => visual_transform(&previous.0),
+            _ => current,
+        };
+        *transform = Transform {
+            translation: previous.translation.lerp(current.translation, overstep),
+            rotation: previous.rotation.slerp(current.rotation, overstep),
+            scale: previous.scale.lerp(current.scale, overstep),
+        };
     }
 }
@@ -0,0 +1,91 @@
+#![allow(clippy::disallowed_types)]
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+use whitelace_core::main::schedule::{FixedUpdate, Physics};
+use whitelace_math::Fx;
+use whitelace_sync::{MultiworldApp, SyncPlugin, SyncTarget, SyncWorld, WorldLabel, Worlds};
+use whitelace_transform::{FixedTransform, NoInterpolation, TransformPlugin};
+
+#[derive(WorldLabel, Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Sim;
+
+fn step(mut transforms: Query<&mut FixedTransform>) {
+    for mut transform in &mut transforms {
+        transform.position.x += Fx::ONE;
+    }
+}
+
+/// An entity moving one unit per tick in `schedule`, half a tick into the next frame, and a
+/// smooth and a snapping visual of it.
+fn app(schedule: impl ScheduleLabel) -> (App, Entity, Entity) {
+    let mut app = App::new();
+    let mut target = Entity::PLACEHOLDER;
+    let mut fixed = Time::<Fixed>::default();
+    fixed.accumulate_overstep(fixed.timestep() / 2);
+    app.add_plugins(SyncPlugin)
+        .add_world(Sim)
+        .add_plugins(TransformPlugin::<Sim>::default())
+        .add_world_systems(Sim, schedule, step)
+        .modify_world(Sim, |world| {
+            target = world.spawn(FixedTransform::from_xyz(0, 0, 0)).id();
+        })
+        .insert_resource(fixed);
+    let smooth = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            SyncTarget(target),
+            SyncWorld(Sim.intern()),
+        ))
+        .id();
+    let snap = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            SyncTarget(target),
+            SyncWorld(Sim.intern()),
+            NoInterpolation,
+        ))
+        .id();
+    (app, smooth, snap)
+}
+
+fn tick(app: &mut App, ticks: usize) {
+    let mut worlds = app.world_mut().resource_mut::<Worlds>();
+    for _ in 0..ticks {
+        worlds.get_mut(Sim).unwrap().tick(Vec::new());
+    }
+}
+
+fn x(app: &App, visual: Entity) -> f32 {
+    app.world().get::<Transform>(visual).unwrap().translation.x
+}
+
+#[test]
+fn visuals_blend_the_last_two_ticks() {
+    let (mut app, smooth, snap) = app(FixedUpdate);
+
+    // Three ticks in one frame blend the second and the third, not the first and the third.
+    tick(&mut app, 3);
+    app.update();
+    assert_eq!(x(&app, smooth), 3.0);
+    assert_eq!(x(&app, snap), 3.5);
+
+    tick(&mut app, 1);
+    app.update();
+    assert_eq!(x(&app, smooth), 4.0);
+
+    // A frame without a tick keeps the blend.
+    app.update();
+    assert_eq!(x(&app, smooth), 4.0);
+}
+
+#[test]
+fn physics_moves_are_blended_too() {
+    // Physics runs before any other schedule of the tick but the one remembering transforms.
+    let (mut app, smooth, snap) = app(Physics);
+    tick(&mut app, 3);
+    app.update();
+    assert_eq!(x(&app, smooth), 3.0);
+    assert_eq!(x(&app, snap), 3.5);
+}